@@ -1,10 +1,16 @@
-use cosmwasm_std::{from_binary, to_binary, attr, ensure, coins, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, QueryRequest, Response, Storage, Timestamp, Uint128, WasmMsg, WasmQuery};
+use cosmwasm_std::{from_json, to_json_binary, attr, ensure, Addr, Api, Attribute, Binary, BlockInfo, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, Timestamp, Uint128, WasmMsg, WasmQuery};
 use crate::{
-    msg::{Cw721CustomMsg},
-    state::{BIDS, TOKEN_AUCTION_STATE, NEXT_AUCTION_ID, AuctionInfo, Bid, OrderBy, TokenAuctionState, auction_infos, read_auction_infos, read_bids},
+    msg::{Cw20HookMsg, Cw2981ExtensionMsg, Cw2981QueryMsg, Cw721CustomMsg, MigrateMsg, OracleQueryMsg, PriceResponse, RoyaltiesInfoResponse},
+    state::{AUTO_SEND, BALANCES, BIDDER_ALLOWLISTS, BIDS, BUNDLE_DRAFTS, BUNDLES, COLLECTIONS, COMMITTED_ASSETS, COMMITTED_BIDS, CONFIG, LEGACY_CONFIG, LEGACY_TOKEN_AUCTION_STATE, TOKEN_AUCTION_STATE, NEXT_AUCTION_ID, RATES, RESERVE_PRICES, SEALED_BIDS, SECOND_PRICES, Asset, AssetInfo, AuctionInfo, AuctionType, Bid, BidIncrement, BidderAllowlist, BundleItem, CollectionInfo, CollectionOffer, CollectionStatus, Config, LegacyConfig, Listing, Offer, OrderBy, PriceDecay, PriceSource, SealedBid, SealedPricing, TokenAuctionState, auction_infos, collection_offers, legacy_auction_infos, listings, offers, read_auction_infos, read_bids, read_collection_offers, read_collections, read_listings, read_offers},
     error::{ContractError},
 };
-use cw721::{Cw721ReceiveMsg, Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse, Expiration};
+use cw20::Cw20ReceiveMsg;
+use cw721::{Cw721ReceiveMsg, Cw721ExecuteMsg, Cw721QueryMsg, OwnerOfResponse, TokensResponse, Expiration};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+pub const MAX_FEE_BPS: u64 = 10_000;
 
 // ============================== execute handlers ==============================//
 pub fn exec_handle_receive_cw721(
@@ -13,12 +19,27 @@ pub fn exec_handle_receive_cw721(
     info: MessageInfo,
     msg: Cw721ReceiveMsg,
 ) -> Result<Response, ContractError> {
-    match from_binary(&msg.msg)? {
+    // ReceiveNft can be called by anyone, so make sure the token really arrived.
+    ensure_collection_allowed(deps.storage, &info.sender)?;
+    let owner = owner_of_token(deps.querier, info.sender.to_string(), msg.token_id.clone())?;
+    ensure!(
+        owner.owner == env.contract.address,
+        ContractError::TokenNotReceived {}
+    );
+
+    match from_json(&msg.msg)? {
         Cw721CustomMsg::StartAuction {
             start_time,
             duration,
-            coin_denom,
+            asset,
             min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets,
+            allowlist,
         } => exec_start_auction(
             deps,
             env,
@@ -27,8 +48,93 @@ pub fn exec_handle_receive_cw721(
             info.sender.to_string(),
             start_time,
             duration,
-            coin_denom,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets.unwrap_or_default(),
+            allowlist,
+        ),
+        Cw721CustomMsg::StartDutchAuction {
+            start_time,
+            duration,
+            asset,
+            start_price,
+            end_price,
+            decay,
+        } => exec_start_dutch_auction(
+            deps,
+            env,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+            start_time,
+            duration,
+            asset,
+            start_price,
+            end_price,
+            decay,
+        ),
+        Cw721CustomMsg::StartSealedAuction {
+            start_time,
+            duration,
+            reveal_duration,
+            asset,
+            min_bid,
+            pricing,
+            forfeit_unrevealed,
+        } => exec_start_sealed_auction(
+            deps,
+            env,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+            start_time,
+            duration,
+            reveal_duration,
+            asset,
             min_bid,
+            pricing,
+            forfeit_unrevealed,
+        ),
+        Cw721CustomMsg::AddToBundle {} => exec_add_to_bundle(
+            deps,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+        ),
+        Cw721CustomMsg::ListForSale {
+            price,
+            denom,
+            expires,
+        } => exec_list_for_sale(
+            deps,
+            env,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+            price,
+            denom,
+            expires,
+        ),
+        Cw721CustomMsg::AcceptOffer { bidder } => exec_accept_offer(
+            deps,
+            env,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+            bidder,
+        ),
+        Cw721CustomMsg::AcceptCollectionOffer { bidder } => exec_accept_collection_offer(
+            deps,
+            env,
+            msg.sender,
+            msg.token_id,
+            info.sender.to_string(),
+            bidder,
         ),
     }
 }
@@ -42,39 +148,49 @@ fn exec_start_auction(
     token_address: String,
     start_time: u64,
     duration: u64,
-    coin_denom: String,
+    asset: AssetInfo,
     min_bid: Option<Uint128>,
+    reserve_price: Option<Uint128>,
+    buy_now_price: Option<Uint128>,
+    extension_window: Option<u64>,
+    extension_duration: Option<u64>,
+    min_increment: Option<BidIncrement>,
+    accepted_assets: Vec<AssetInfo>,
+    allowlist: Option<BidderAllowlist>,
 ) -> Result<Response, ContractError> {
+    validate_auction_prices(min_bid, reserve_price, buy_now_price)?;
     ensure!(
-        start_time > 0 && duration > 0,
-        ContractError::InvalidExpiration {}
+        extension_window.is_some() == extension_duration.is_some()
+            && extension_window.is_none_or(|window| window > 0 && window <= u64::MAX / 1000000)
+            && extension_duration.is_none_or(|duration| duration > 0 && duration <= u64::MAX / 1000000),
+        ContractError::InvalidExtension {}
     );
-
-    let start_expiration = millisecond_to_expiration(start_time)?;
-    let end_expiration = millisecond_to_expiration(start_time + duration)?;
-
-    let block_time = block_to_expiration(&env.block, start_expiration).unwrap();
-    ensure!(
-        start_expiration.gt(&block_time),
-        ContractError::InvalidStartTime {
-            current_time: env.block.time.nanos() / 1000000,
-            current_block: env.block.height,
+    ensure_asset_allowed(deps.storage, &token_address, &asset)?;
+    for accepted_asset in accepted_assets.iter() {
+        ensure_asset_allowed(deps.storage, &token_address, accepted_asset)?;
+    }
+    match &allowlist {
+        Some(BidderAllowlist::Addresses { addresses }) => {
+            ensure!(!addresses.is_empty(), ContractError::InvalidAllowlist {});
+            for address in addresses {
+                deps.api.addr_validate(address.as_str())?;
+            }
         }
-    );
-
-    let auction_id = get_and_increment_next_auction_id(deps.storage)?;
-    let pk = token_id.to_owned() + &token_address;
-
-    let mut auction_info = auction_infos().load(deps.storage, &pk).unwrap_or_default();
-    auction_info.push(auction_id);
-    if auction_info.token_address.is_empty() {
-        auction_info.token_address = token_address.to_owned();
-        auction_info.token_id = token_id.to_owned();
+        Some(BidderAllowlist::Holders { collection }) => {
+            deps.api.addr_validate(collection.as_str())?;
+        }
+        None => {}
     }
-    auction_infos().save(deps.storage, &pk, &auction_info)?;
-    
-    BIDS.save(deps.storage, auction_id.u128(), &vec![])?;
+    let min_increment = min_increment.or(CONFIG.load(deps.storage)?.default_min_increment);
 
+    let (auction_id, start_expiration, end_expiration) =
+        register_auction(deps.storage, &env, &token_address, &token_id, start_time, duration)?;
+    if let Some(reserve_price) = reserve_price {
+        RESERVE_PRICES.save(deps.storage, auction_id.u128(), &reserve_price)?;
+    }
+    if let Some(allowlist) = allowlist {
+        BIDDER_ALLOWLISTS.save(deps.storage, auction_id.u128(), &allowlist)?;
+    }
 
     TOKEN_AUCTION_STATE.save(
         deps.storage,
@@ -84,241 +200,2260 @@ fn exec_start_auction(
             end_time: end_expiration,
             high_bidder_addr: Addr::unchecked(""),
             high_bidder_amount: Uint128::zero(),
-            coin_denom: coin_denom.clone(),
+            asset: asset.clone(),
             auction_id,
             min_bid,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
             owner: sender,
             token_id,
             token_address,
             is_cancelled: false,
+            is_claimed: false,
+            proxy_max: None,
+            accepted_assets,
+            high_bid_asset: None,
+            auction_type: AuctionType::English,
         },
     )?;
     Ok(Response::new().add_attributes(vec![
         attr("action", "start_auction"),
         attr("start_time", start_expiration.to_string()),
         attr("end_time", end_expiration.to_string()),
-        attr("coin_denom", coin_denom),
+        attr("coin_denom", asset.to_string()),
         attr("auction_id", auction_id.to_string()),
     ]))
 }
 
-pub fn exec_place_bid(
+#[allow(clippy::too_many_arguments)]
+fn exec_start_dutch_auction(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    sender: String,
     token_id: String,
     token_address: String,
+    start_time: u64,
+    duration: u64,
+    asset: AssetInfo,
+    start_price: Uint128,
+    end_price: Uint128,
+    decay: PriceDecay,
 ) -> Result<Response, ContractError> {
-    let mut token_auction_state = get_token_auction_state(deps.storage, &token_id, &token_address)?;
-
-    ensure!(
-        !token_auction_state.is_cancelled,
-        ContractError::AuctionCancelled {}
-    );
-
-    ensure!(
-        token_auction_state.start_time.is_expired(&env.block),
-        ContractError::AuctionNotStarted {}
-    );
-    ensure!(
-        !token_auction_state.end_time.is_expired(&env.block),
-        ContractError::AuctionEnded {}
-    );
-
-    ensure!(
-        token_auction_state.owner != info.sender,
-        ContractError::TokenOwnerCannotBid {}
-    );
-
-    ensure!(
-        info.funds.len() == 1,
-        ContractError::InvalidFunds {
-            msg: "Auctions require exactly one coin to be sent.".to_string(),
-        }
-    );
-
-    ensure!(
-        token_auction_state.high_bidder_addr != info.sender,
-        ContractError::HighestBidderCannotOutBid {}
-    );
-
-    let coin_denom = token_auction_state.coin_denom.clone();
-    let payment: &Coin = &info.funds[0];
-    ensure!(
-        payment.denom == coin_denom && payment.amount > Uint128::zero(),
-        ContractError::InvalidFunds {
-            msg: format!("No {} assets are provided to auction", coin_denom),
-        }
-    );
-    ensure!(
-        token_auction_state.high_bidder_amount < payment.amount,
-        ContractError::BidSmallerThanHighestBid {}
-    );
-
-    let mut messages: Vec<CosmosMsg> = vec![];
-    // Send back previous bid unless there was no previous bid.
-    if token_auction_state.high_bidder_amount > Uint128::zero() {
-        let bank_msg = BankMsg::Send {
-            to_address: token_auction_state.high_bidder_addr.to_string(),
-            amount: coins(
-                token_auction_state.high_bidder_amount.u128(),
-                token_auction_state.coin_denom.clone(),
-            ),
-        };
-        messages.push(CosmosMsg::Bank(bank_msg));
+    ensure!(start_price > end_price, ContractError::InvalidPrice {});
+    if let PriceDecay::Stepwise { interval } = decay {
+        ensure!(
+            interval > 0 && interval <= duration,
+            ContractError::InvalidPriceDecay {}
+        );
     }
+    ensure_asset_allowed(deps.storage, &token_address, &asset)?;
 
-    token_auction_state.high_bidder_addr = info.sender.clone();
-    token_auction_state.high_bidder_amount = payment.amount;
-
-    let key = token_auction_state.auction_id.u128();
-    TOKEN_AUCTION_STATE.save(deps.storage, key.clone(), &token_auction_state)?;
-    let mut bids_for_auction = BIDS.load(deps.storage, key.clone())?;
-    bids_for_auction.push(Bid {
-        bidder: info.sender.to_string(),
-        amount: payment.amount,
-        timestamp: env.block.time,
-    });
-    BIDS.save(deps.storage, key, &bids_for_auction)?;
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "bid"),
-        attr("token_id", token_id),
-        attr("bider", info.sender.to_string()),
-        attr("amount", payment.amount.to_string()),
+    let (auction_id, start_expiration, end_expiration) =
+        register_auction(deps.storage, &env, &token_address, &token_id, start_time, duration)?;
+    TOKEN_AUCTION_STATE.save(
+        deps.storage,
+        auction_id.u128(),
+        &TokenAuctionState {
+            start_time: start_expiration,
+            end_time: end_expiration,
+            high_bidder_addr: Addr::unchecked(""),
+            high_bidder_amount: Uint128::zero(),
+            asset: asset.clone(),
+            auction_id,
+            min_bid: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            owner: sender,
+            token_id,
+            token_address,
+            is_cancelled: false,
+            is_claimed: false,
+            proxy_max: None,
+            accepted_assets: vec![],
+            high_bid_asset: None,
+            auction_type: AuctionType::Dutch {
+                start_price,
+                end_price,
+                decay,
+            },
+        },
+    )?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "start_dutch_auction"),
+        attr("start_time", start_expiration.to_string()),
+        attr("end_time", end_expiration.to_string()),
+        attr("coin_denom", asset.to_string()),
+        attr("auction_id", auction_id.to_string()),
+        attr("start_price", start_price.to_string()),
+        attr("end_price", end_price.to_string()),
     ]))
 }
 
-pub fn exec_cancel(
+#[allow(clippy::too_many_arguments)]
+fn exec_start_sealed_auction(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    sender: String,
     token_id: String,
     token_address: String,
+    start_time: u64,
+    duration: u64,
+    reveal_duration: u64,
+    asset: AssetInfo,
+    min_bid: Option<Uint128>,
+    pricing: SealedPricing,
+    forfeit_unrevealed: bool,
 ) -> Result<Response, ContractError> {
-    let mut token_auction_state = get_token_auction_state(deps.storage, &token_id, &token_address)?;
-    ensure!(
-        info.sender == token_auction_state.owner,
-        ContractError::Unauthorized {}
-    );
-    ensure!(
-        !token_auction_state.end_time.is_expired(&env.block),
-        ContractError::AuctionEnded {}
-    );
-    let mut messages: Vec<CosmosMsg> = vec![CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: token_auction_state.token_address.clone(),
-        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-            recipient: info.sender.to_string(),
-            token_id,
-        })?,
-        funds: vec![],
-    })];
-
-    // Refund highest bid, if it exists.
-    if !token_auction_state.high_bidder_amount.is_zero() {
-        messages.push(CosmosMsg::Bank(BankMsg::Send {
-            to_address: token_auction_state.high_bidder_addr.to_string(),
-            amount: coins(
-                token_auction_state.high_bidder_amount.u128(),
-                token_auction_state.coin_denom.clone(),
-            ),
-        }));
+    ensure!(reveal_duration > 0, ContractError::InvalidExpiration {});
+    if pricing == SealedPricing::SecondPrice {
+        ensure!(
+            min_bid.is_some_and(|min_bid| !min_bid.is_zero()),
+            ContractError::MinBidRequired {}
+        );
     }
+    ensure_asset_allowed(deps.storage, &token_address, &asset)?;
 
-    token_auction_state.is_cancelled = true;
+    let bidding_end = start_time
+        .checked_add(duration)
+        .ok_or(ContractError::InvalidExpiration {})?;
+    let total_duration = duration
+        .checked_add(reveal_duration)
+        .ok_or(ContractError::InvalidExpiration {})?;
+    let reveal_start = millisecond_to_expiration(bidding_end)?;
+    let (auction_id, start_expiration, end_expiration) = register_auction(
+        deps.storage,
+        &env,
+        &token_address,
+        &token_id,
+        start_time,
+        total_duration,
+    )?;
     TOKEN_AUCTION_STATE.save(
         deps.storage,
-        token_auction_state.auction_id.u128(),
-        &token_auction_state,
+        auction_id.u128(),
+        &TokenAuctionState {
+            start_time: start_expiration,
+            end_time: end_expiration,
+            high_bidder_addr: Addr::unchecked(""),
+            high_bidder_amount: Uint128::zero(),
+            asset: asset.clone(),
+            auction_id,
+            min_bid,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            owner: sender,
+            token_id,
+            token_address,
+            is_cancelled: false,
+            is_claimed: false,
+            proxy_max: None,
+            accepted_assets: vec![],
+            high_bid_asset: None,
+            auction_type: AuctionType::Sealed {
+                reveal_start,
+                pricing,
+                forfeit_unrevealed,
+            },
+        },
     )?;
-
-    Ok(Response::new().add_messages(messages))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "start_sealed_auction"),
+        attr("start_time", start_expiration.to_string()),
+        attr("reveal_start", reveal_start.to_string()),
+        attr("end_time", end_expiration.to_string()),
+        attr("coin_denom", asset.to_string()),
+        attr("auction_id", auction_id.to_string()),
+    ]))
 }
 
-pub fn exec_claim(
+fn exec_add_to_bundle(
     deps: DepsMut,
-    env: Env,
-    _info: MessageInfo,
+    sender: String,
     token_id: String,
     token_address: String,
 ) -> Result<Response, ContractError> {
-    let token_auction_state = get_token_auction_state(deps.storage, &token_id, &token_address)?;
-    ensure!(
-        token_auction_state.end_time.is_expired(&env.block),
-        ContractError::AuctionNotEnded {}
-    );
-    let token_owner = owner_of_token(
-        deps.querier,
-        token_auction_state.token_address.clone(),
-        token_id.clone(),
-    )?.owner;
-    ensure!(
-        token_owner == env.contract.address,
-        ContractError::AuctionAlreadyClaimed {}
-    );
+    let seller = deps.api.addr_validate(&sender)?;
+    let mut draft = BUNDLE_DRAFTS.may_load(deps.storage, &seller)?.unwrap_or_default();
+    draft.push(BundleItem {
+        token_address: token_address.clone(),
+        token_id: token_id.clone(),
+    });
+    BUNDLE_DRAFTS.save(deps.storage, &seller, &draft)?;
 
-    if token_auction_state.high_bidder_addr.to_string().is_empty() || token_auction_state.high_bidder_amount.is_zero() {
-        return Ok(Response::new()
-            .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: token_auction_state.token_address.clone(),
-                msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-                    recipient: token_auction_state.owner.clone(),
-                    token_id: token_id.clone(),
-                })?,
-                funds: vec![],
-            }))
-            .add_attribute("action", "claim")
-            .add_attribute("token_id", token_id)
-            .add_attribute("token_contract", token_auction_state.token_address)
-            .add_attribute("recipient", token_auction_state.owner)
-            .add_attribute("winning_bid_amount", token_auction_state.high_bidder_amount)
-            .add_attribute("auction_id", token_auction_state.auction_id));
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_to_bundle"),
+        attr("seller", seller),
+        attr("token_id", token_id),
+        attr("token_contract", token_address),
+        attr("bundle_size", draft.len().to_string()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exec_start_bundle_auction(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_time: u64,
+    duration: u64,
+    asset: AssetInfo,
+    min_bid: Option<Uint128>,
+    reserve_price: Option<Uint128>,
+    buy_now_price: Option<Uint128>,
+    extension_window: Option<u64>,
+    extension_duration: Option<u64>,
+    min_increment: Option<BidIncrement>,
+    accepted_assets: Option<Vec<AssetInfo>>,
+    allowlist: Option<BidderAllowlist>,
+) -> Result<Response, ContractError> {
+    let accepted_assets = accepted_assets.unwrap_or_default();
+    let items = BUNDLE_DRAFTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    ensure!(!items.is_empty(), ContractError::EmptyBundle {});
+    // Collections denied while the draft was open cannot be auctioned.
+    for item in items.iter() {
+        ensure_collection_allowed(deps.storage, &Addr::unchecked(&item.token_address))?;
     }
+    ensure_bundle_assets_allowed(deps.storage, &items, &asset, &accepted_assets)?;
+    BUNDLE_DRAFTS.remove(deps.storage, &info.sender);
 
-    
-    Ok(Response::new()
-        // Send funds to the original owner.
-        .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: token_auction_state.owner,
-            amount: coins(
-                token_auction_state.high_bidder_amount.u128(),
-                token_auction_state.coin_denom.clone(),
-            ),
-        }))
-        // Send NFT to auction winner.
-        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: token_auction_state.token_address.clone(),
-            msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-                recipient: token_auction_state.high_bidder_addr.to_string(),
-                token_id: token_id.clone(),
-            })?,
-            funds: vec![],
-        }))
-        .add_attribute("action", "claim")
-        .add_attribute("token_id", token_id)
-        .add_attribute("token_contract", token_auction_state.token_address)
-        .add_attribute("recipient", &token_auction_state.high_bidder_addr)
-        .add_attribute("winning_bid_amount", token_auction_state.high_bidder_amount)
-        .add_attribute("auction_id", token_auction_state.auction_id))
+    // The first token stands for the whole bundle on the auction state.
+    let first = &items[0];
+    let response = exec_start_auction(
+        deps.branch(),
+        env,
+        info.sender.to_string(),
+        first.token_id.clone(),
+        first.token_address.clone(),
+        start_time,
+        duration,
+        asset,
+        min_bid,
+        reserve_price,
+        buy_now_price,
+        extension_window,
+        extension_duration,
+        min_increment,
+        accepted_assets,
+        allowlist,
+    )?;
+    let auction_id = latest_auction_id(deps.storage, &first.token_id, &first.token_address)?;
+    for item in &items[1..] {
+        record_auction_info(deps.storage, &item.token_address, &item.token_id, auction_id)?;
+    }
+    BUNDLES.save(deps.storage, auction_id.u128(), &items)?;
+
+    Ok(response.add_attribute("bundle_size", items.len().to_string()))
 }
 
-// ============================== query handlers ==============================//
-pub fn query_auction_infos(
-    deps: Deps,
-    token_address: Option<String>,
-    start_after: Option<String>,
-    limit: Option<u64>,
-) -> Result<Vec<AuctionInfo>, ContractError> {
-    read_auction_infos(deps.storage, token_address, start_after, limit)
+/// Checks the assets against the bundle tokens other than the first one, which
+/// `exec_start_auction` checks itself.
+fn ensure_bundle_assets_allowed(
+    storage: &dyn Storage,
+    items: &[BundleItem],
+    asset: &AssetInfo,
+    accepted_assets: &[AssetInfo],
+) -> Result<(), ContractError> {
+    for item in items.iter().skip(1) {
+        ensure_asset_allowed(storage, &item.token_address, asset)?;
+        for accepted_asset in accepted_assets.iter() {
+            ensure_asset_allowed(storage, &item.token_address, accepted_asset)?;
+        }
+    }
+    Ok(())
 }
 
+pub fn exec_cancel_bundle_draft(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let items = BUNDLE_DRAFTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    ensure!(!items.is_empty(), ContractError::EmptyBundle {});
+    BUNDLE_DRAFTS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_messages(nft_transfer_messages(&items, info.sender.as_str())?)
+        .add_attribute("action", "cancel_bundle_draft")
+        .add_attribute("seller", info.sender)
+        .add_attribute("bundle_size", items.len().to_string()))
+}
+
+fn validate_auction_prices(
+    min_bid: Option<Uint128>,
+    reserve_price: Option<Uint128>,
+    buy_now_price: Option<Uint128>,
+) -> Result<(), ContractError> {
+    if let Some(reserve_price) = reserve_price {
+        ensure!(
+            !reserve_price.is_zero() && reserve_price >= min_bid.unwrap_or_default(),
+            ContractError::InvalidReservePrice {}
+        );
+    }
+    if let Some(buy_now_price) = buy_now_price {
+        ensure!(
+            !buy_now_price.is_zero()
+                && buy_now_price >= min_bid.unwrap_or_default()
+                && buy_now_price >= reserve_price.unwrap_or_default(),
+            ContractError::InvalidBuyNowPrice {}
+        );
+    }
+    Ok(())
+}
+
+/// Checks an auction window starting in the future and returns its start and end.
+fn auction_window(env: &Env, start_time: u64, duration: u64) -> Result<(Expiration, Expiration), ContractError> {
+    ensure!(
+        start_time > 0 && duration > 0,
+        ContractError::InvalidExpiration {}
+    );
+    let start_expiration = millisecond_to_expiration(start_time)?;
+    let end_time = start_time
+        .checked_add(duration)
+        .ok_or(ContractError::InvalidExpiration {})?;
+    let end_expiration = millisecond_to_expiration(end_time)?;
+
+    let block_time = block_to_expiration(&env.block, start_expiration).unwrap();
+    ensure!(
+        start_expiration.gt(&block_time),
+        ContractError::InvalidStartTime {
+            current_time: env.block.time.nanos() / 1000000,
+            current_block: env.block.height,
+        }
+    );
+    Ok((start_expiration, end_expiration))
+}
+
+/// Checks the auction window, assigns the next auction id and records it
+/// against the token.
+fn register_auction(
+    storage: &mut dyn Storage,
+    env: &Env,
+    token_address: &str,
+    token_id: &str,
+    start_time: u64,
+    duration: u64,
+) -> Result<(Uint128, Expiration, Expiration), ContractError> {
+    let (start_expiration, end_expiration) = auction_window(env, start_time, duration)?;
+
+    let auction_id = get_and_increment_next_auction_id(storage)?;
+    record_auction_info(storage, token_address, token_id, auction_id)?;
+
+    BIDS.save(storage, auction_id.u128(), &vec![])?;
+    Ok((auction_id, start_expiration, end_expiration))
+}
+
+fn record_auction_info(
+    storage: &mut dyn Storage,
+    token_address: &str,
+    token_id: &str,
+    auction_id: Uint128,
+) -> Result<(), ContractError> {
+    let token_addr = Addr::unchecked(token_address);
+    let pk = (&token_addr, token_id);
+
+    let mut auction_info = auction_infos().load(storage, pk).unwrap_or_default();
+    auction_info.push(auction_id);
+    if auction_info.token_address.is_empty() {
+        auction_info.token_address = token_address.to_owned();
+        auction_info.token_id = token_id.to_owned();
+    }
+    auction_infos().save(storage, pk, &auction_info)?;
+    Ok(())
+}
+
+pub fn exec_handle_receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let bidder = deps.api.addr_validate(&msg.sender)?;
+    let payment = Asset {
+        info: AssetInfo::Cw20 {
+            contract_addr: info.sender.to_string(),
+        },
+        amount: msg.amount,
+    };
+    match from_json(&msg.msg)? {
+        Cw20HookMsg::PlaceBid {
+            token_id,
+            token_address,
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            place_bid(deps, env, bidder, auction_id, payment, BidKind::Manual)
+        }
+        Cw20HookMsg::PlaceBidById { auction_id } => {
+            place_bid(deps, env, bidder, auction_id, payment, BidKind::Manual)
+        }
+        Cw20HookMsg::BuyNow {
+            token_id,
+            token_address,
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            place_bid(deps, env, bidder, auction_id, payment, BidKind::BuyNow)
+        }
+        Cw20HookMsg::BuyNowById { auction_id } => {
+            place_bid(deps, env, bidder, auction_id, payment, BidKind::BuyNow)
+        }
+        Cw20HookMsg::PlaceProxyBid { auction_id } => {
+            place_bid(deps, env, bidder, auction_id, payment, BidKind::Proxy)
+        }
+        Cw20HookMsg::MakeOffer {
+            token_address,
+            token_id,
+            expires,
+        } => make_offer(deps, env, bidder, token_address, token_id, expires, payment),
+        Cw20HookMsg::CommitBid {
+            auction_id,
+            commitment,
+        } => commit_bid(deps, env, bidder, auction_id, commitment, payment),
+        Cw20HookMsg::MakeCollectionOffer {
+            token_address,
+            price,
+            quantity,
+            expires,
+        } => make_collection_offer(deps, env, bidder, token_address, price, quantity, expires, payment),
+    }
+}
+
+pub fn exec_place_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    place_bid(deps, env, info.sender, auction_id, payment, BidKind::Manual)
+}
+
+pub fn exec_buy_now(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    place_bid(deps, env, info.sender, auction_id, payment, BidKind::BuyNow)
+}
+
+pub fn exec_place_proxy_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    place_bid(deps, env, info.sender, auction_id, payment, BidKind::Proxy)
+}
+
+#[derive(PartialEq)]
+enum BidKind {
+    Manual,
+    BuyNow,
+    /// Escrows a maximum the contract bids up to on the bidder's behalf.
+    Proxy,
+}
+
+fn place_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    auction_id: Uint128,
+    payment: Asset,
+    kind: BidKind,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    let token_id = token_auction_state.token_id.clone();
+
+    ensure!(
+        !matches!(token_auction_state.auction_type, AuctionType::Sealed { .. }),
+        ContractError::SealedBidRequired {}
+    );
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+
+    ensure!(
+        token_auction_state.start_time.is_expired(&env.block),
+        ContractError::AuctionNotStarted {}
+    );
+    ensure!(
+        !token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionEnded {}
+    );
+
+    ensure!(
+        token_auction_state.owner != bidder,
+        ContractError::TokenOwnerCannotBid {}
+    );
+    ensure_bidder_allowed(deps.as_ref(), auction_id, &bidder)?;
+
+    let asset = token_auction_state.asset.clone();
+    let in_asset = payment.info == asset;
+    ensure!(
+        (in_asset || token_auction_state.accepted_assets.contains(&payment.info))
+            && payment.amount > Uint128::zero(),
+        ContractError::InvalidFunds {
+            msg: format!("No {} assets are provided to auction", asset),
+        }
+    );
+    // Other accepted assets can only be used for plain bids.
+    ensure!(
+        in_asset || kind == BidKind::Manual,
+        ContractError::InvalidFunds {
+            msg: format!("Only {} can be used for this bid", asset),
+        }
+    );
+    // Funds the bidder already committed to this auction count towards the
+    // bid, so raising only takes the difference.
+    let key = token_auction_state.auction_id.u128();
+    let committed = COMMITTED_BIDS
+        .may_load(deps.storage, (key, &bidder))?
+        .unwrap_or_default();
+    if !committed.is_zero() {
+        let committed_asset = COMMITTED_ASSETS
+            .may_load(deps.storage, (key, &bidder))?
+            .unwrap_or_else(|| asset.clone());
+        ensure!(
+            committed_asset == payment.info,
+            ContractError::InvalidFunds {
+                msg: format!("Committed funds are in {}", committed_asset),
+            }
+        );
+    }
+    let paid = committed + payment.amount;
+    // Bids are compared by their value in the auction's asset.
+    let total = if in_asset {
+        paid
+    } else {
+        asset_value(
+            deps.as_ref(),
+            &Asset {
+                info: payment.info.clone(),
+                amount: paid,
+            },
+            &asset,
+        )?
+    };
+    if let Some(min_bid) = token_auction_state.min_bid {
+        ensure!(
+            total >= min_bid,
+            ContractError::BidLowerThanMinBid { min_bid }
+        );
+    }
+    // Any payment on a Dutch auction is a purchase at the current price.
+    let dutch_price = dutch_price(&token_auction_state, &env.block);
+    if let Some(price) = dutch_price {
+        ensure!(
+            kind != BidKind::Proxy,
+            ContractError::ProxyBidNotSupported {}
+        );
+        ensure!(
+            total >= price,
+            ContractError::PriceNotMet { price }
+        );
+    } else if kind == BidKind::BuyNow {
+        let buy_now_price = token_auction_state
+            .buy_now_price
+            .ok_or(ContractError::BuyNowNotAvailable {})?;
+        ensure!(
+            total >= buy_now_price,
+            ContractError::BuyNowPriceNotMet { buy_now_price }
+        );
+    }
+    ensure!(
+        token_auction_state.high_bidder_amount < total,
+        ContractError::BidSmallerThanHighestBid {}
+    );
+
+    // Paying the buy now price, or buying on a Dutch auction, closes the
+    // auction and settles it right away. Proxy maximums and bids in other
+    // accepted assets never buy outright.
+    let buy_now_price = dutch_price.or_else(|| {
+        token_auction_state
+            .buy_now_price
+            .filter(|buy_now_price| in_asset && kind != BidKind::Proxy && total >= *buy_now_price)
+    });
+    if buy_now_price.is_none() {
+        let min_required = min_next_bid(&token_auction_state);
+        ensure!(
+            total >= min_required,
+            ContractError::BidIncrementTooSmall { min_required }
+        );
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![];
+    let steps = if let Some(bid_amount) = buy_now_price {
+        token_auction_state.high_bidder_addr = bidder.clone();
+        token_auction_state.high_bidder_amount = bid_amount;
+        token_auction_state.proxy_max = None;
+        vec![(bidder.clone(), bid_amount)]
+    } else {
+        let reserve = RESERVE_PRICES.may_load(deps.storage, key)?.unwrap_or_default();
+        resolve_bid(&mut token_auction_state, &bidder, total, kind == BidKind::Proxy, reserve)
+    };
+    // The bidder's own step comes last unless an automatic bid answered it.
+    let bid_amount = steps
+        .iter()
+        .rev()
+        .find(|(step_bidder, _)| *step_bidder == bidder)
+        .map_or(token_auction_state.high_bidder_amount, |(_, amount)| *amount);
+    COMMITTED_BIDS.save(deps.storage, (key, &bidder), &buy_now_price.unwrap_or(paid))?;
+    if !in_asset {
+        COMMITTED_ASSETS.save(deps.storage, (key, &bidder), &payment.info)?;
+    }
+    if token_auction_state.high_bidder_addr == bidder {
+        token_auction_state.high_bid_asset = (!in_asset).then(|| Asset {
+            info: payment.info.clone(),
+            amount: paid,
+        });
+    } else {
+        attributes.push(attr("high_bidder", token_auction_state.high_bidder_addr.to_string()));
+        attributes.push(attr("high_bid", token_auction_state.high_bidder_amount.to_string()));
+    }
+
+    if buy_now_price.is_some() {
+        token_auction_state.end_time = Expiration::AtTime(env.block.time);
+        token_auction_state.is_claimed = true;
+        messages.extend(bundle_settlement_messages(
+            deps.as_ref(),
+            &auction_items(deps.storage, &token_auction_state)?,
+            &token_auction_state.owner,
+            bidder.as_str(),
+            bid_amount,
+            &asset,
+        )?);
+        // Refund whatever was paid above the buy now price.
+        if total > bid_amount {
+            messages.push(asset.transfer_msg(bidder.as_str(), total - bid_amount)?);
+        }
+        messages.extend(settle_high_bid(deps.storage, &token_auction_state, true)?);
+    } else if let Some(end_time) = extended_end_time(&token_auction_state, &env.block) {
+        // Bids close to the end push it back so they can still be answered.
+        token_auction_state.end_time = end_time;
+        attributes.push(attr("end_time", end_time.to_string()));
+    }
+
+    TOKEN_AUCTION_STATE.save(deps.storage, key, &token_auction_state)?;
+    let mut bids_for_auction = BIDS.load(deps.storage, key)?;
+    bids_for_auction.extend(steps.into_iter().map(|(step_bidder, amount)| Bid {
+        bidder: step_bidder.to_string(),
+        amount,
+        timestamp: env.block.time,
+    }));
+    BIDS.save(deps.storage, key, &bids_for_auction)?;
+    let action = match kind {
+        _ if buy_now_price.is_some() => "buy_now",
+        BidKind::Proxy => "proxy_bid",
+        _ => "bid",
+    };
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", action),
+        attr("token_id", token_id),
+        attr("bider", bidder.to_string()),
+        attr("amount", bid_amount.to_string()),
+    ]).add_attributes(attributes))
+}
+
+fn ensure_bidder_allowed(deps: Deps, auction_id: Uint128, bidder: &Addr) -> Result<(), ContractError> {
+    let allowed = match BIDDER_ALLOWLISTS.may_load(deps.storage, auction_id.u128())? {
+        None => true,
+        Some(BidderAllowlist::Addresses { addresses }) => addresses.contains(bidder),
+        Some(BidderAllowlist::Holders { collection }) => {
+            let res: TokensResponse = deps.querier.query_wasm_smart(
+                collection,
+                &Cw721QueryMsg::Tokens {
+                    owner: bidder.to_string(),
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )?;
+            !res.tokens.is_empty()
+        }
+    };
+    ensure!(allowed, ContractError::BidderNotAllowed {});
+    Ok(())
+}
+
+/// Settles a bid of `total` against the current leader, raising proxy bids
+/// in minimum increments up to their maximum, and straight to the reserve
+/// when their maximum covers it. Updates the leader and returns every bid
+/// placed, automatic ones included, in order.
+fn resolve_bid(
+    token_auction_state: &mut TokenAuctionState,
+    bidder: &Addr,
+    total: Uint128,
+    proxy: bool,
+    reserve: Uint128,
+) -> Vec<(Addr, Uint128)> {
+    let leader = token_auction_state.high_bidder_addr.clone();
+    let high_bid = token_auction_state.high_bidder_amount;
+    let leader_max = token_auction_state.proxy_max.unwrap_or(high_bid);
+    let meet_reserve = |amount: Uint128, max: Uint128| {
+        if reserve <= max {
+            amount.max(reserve)
+        } else {
+            amount
+        }
+    };
+
+    // Raising one's own bid: a proxy raises the hidden maximum, and the
+    // visible bid only if the new maximum covers the reserve.
+    if leader == *bidder {
+        if proxy {
+            token_auction_state.proxy_max = Some(total);
+            let amount = meet_reserve(high_bid, total);
+            if amount == high_bid {
+                return vec![];
+            }
+            token_auction_state.high_bidder_amount = amount;
+            return vec![(bidder.clone(), amount)];
+        }
+        token_auction_state.high_bidder_amount = total;
+        token_auction_state.proxy_max = None;
+        return vec![(bidder.clone(), total)];
+    }
+
+    // The leader's proxy answers up to its maximum; ties go to the earlier bid.
+    if total <= leader_max {
+        let answer = meet_reserve(outbid_amount(token_auction_state, total).min(leader_max), leader_max);
+        token_auction_state.high_bidder_amount = answer;
+        return vec![(bidder.clone(), total), (leader, answer)];
+    }
+
+    let mut steps = vec![];
+    if leader_max > high_bid {
+        steps.push((leader, leader_max));
+    }
+    let amount = if !proxy {
+        total
+    } else if high_bid.is_zero() {
+        meet_reserve(token_auction_state.min_bid.unwrap_or(Uint128::one()).max(Uint128::one()), total)
+    } else {
+        meet_reserve(outbid_amount(token_auction_state, leader_max).min(total), total)
+    };
+    token_auction_state.high_bidder_addr = bidder.clone();
+    token_auction_state.high_bidder_amount = amount;
+    token_auction_state.proxy_max = proxy.then_some(total);
+    steps.push((bidder.clone(), amount));
+    steps
+}
+
+pub fn exec_retract_bid(
+    deps: DepsMut,
+    info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    ensure!(
+        token_auction_state.high_bidder_addr != info.sender,
+        ContractError::HighestBidderCannotRetract {}
+    );
+    let key = (auction_id.u128(), &info.sender);
+    let committed = COMMITTED_BIDS
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::NoCommittedBid {})?;
+    let asset = COMMITTED_ASSETS
+        .may_load(deps.storage, key)?
+        .unwrap_or(token_auction_state.asset);
+    COMMITTED_BIDS.remove(deps.storage, key);
+    COMMITTED_ASSETS.remove(deps.storage, key);
+
+    Ok(Response::new()
+        .add_message(asset.transfer_msg(info.sender.as_str(), committed)?)
+        .add_attribute("action", "retract_bid")
+        .add_attribute("auction_id", auction_id)
+        .add_attribute("bidder", info.sender)
+        .add_attribute("amount", committed))
+}
+
+pub fn exec_commit_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    commit_bid(deps, env, info.sender, auction_id, commitment, payment)
+}
+
+fn commit_bid(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    auction_id: Uint128,
+    commitment: Binary,
+    payment: Asset,
+) -> Result<Response, ContractError> {
+    let token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    let reveal_start = match token_auction_state.auction_type {
+        AuctionType::Sealed { reveal_start, .. } => reveal_start,
+        _ => return Err(ContractError::NotSealedAuction {}),
+    };
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        token_auction_state.start_time.is_expired(&env.block),
+        ContractError::AuctionNotStarted {}
+    );
+    ensure!(
+        !reveal_start.is_expired(&env.block),
+        ContractError::AuctionEnded {}
+    );
+    ensure!(
+        token_auction_state.owner != bidder,
+        ContractError::TokenOwnerCannotBid {}
+    );
+    ensure!(
+        payment.info == token_auction_state.asset && !payment.amount.is_zero(),
+        ContractError::InvalidFunds {
+            msg: format!("No {} assets are provided to auction", token_auction_state.asset),
+        }
+    );
+    if let Some(min_bid) = token_auction_state.min_bid {
+        ensure!(
+            payment.amount >= min_bid,
+            ContractError::BidLowerThanMinBid { min_bid }
+        );
+    }
+
+    let key = (auction_id.u128(), &bidder);
+    ensure!(
+        !SEALED_BIDS.has(deps.storage, key),
+        ContractError::BidAlreadyCommitted {}
+    );
+    SEALED_BIDS.save(
+        deps.storage,
+        key,
+        &SealedBid {
+            commitment,
+            deposit: payment.amount,
+            amount: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "commit_bid"),
+        attr("auction_id", auction_id.to_string()),
+        attr("bidder", bidder),
+        attr("deposit", payment.amount.to_string()),
+    ]))
+}
+
+pub fn exec_reveal_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+    amount: Uint128,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    let reveal_start = match token_auction_state.auction_type {
+        AuctionType::Sealed { reveal_start, .. } => reveal_start,
+        _ => return Err(ContractError::NotSealedAuction {}),
+    };
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        reveal_start.is_expired(&env.block),
+        ContractError::RevealNotStarted {}
+    );
+    ensure!(
+        !token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionEnded {}
+    );
+
+    let key = (auction_id.u128(), &info.sender);
+    let mut sealed_bid = SEALED_BIDS
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::NoCommittedBid {})?;
+    ensure!(
+        sealed_bid.amount.is_none(),
+        ContractError::BidAlreadyRevealed {}
+    );
+    // The commitment covers the auction and the bidder, so it cannot be
+    // replayed by another bidder or in another auction.
+    let digest = Sha256::digest(format!("{}:{}:{}:{}", auction_id, info.sender, amount, salt).as_bytes());
+    ensure!(
+        sealed_bid.commitment.as_slice() == &digest[..]
+            && !amount.is_zero()
+            && amount <= sealed_bid.deposit,
+        ContractError::InvalidReveal {}
+    );
+    if let Some(min_bid) = token_auction_state.min_bid {
+        ensure!(amount >= min_bid, ContractError::BidLowerThanMinBid { min_bid });
+    }
+    sealed_bid.amount = Some(amount);
+    SEALED_BIDS.save(deps.storage, key, &sealed_bid)?;
+
+    let asset = token_auction_state.asset.clone();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    // The deposit above the revealed amount is never needed.
+    if sealed_bid.deposit > amount {
+        messages.push(asset.transfer_msg(info.sender.as_str(), sealed_bid.deposit - amount)?);
+    }
+    // Only the highest bid stays in escrow; the other amounts go back
+    // straight away, the best of them becoming the second price.
+    let second_price = SECOND_PRICES
+        .may_load(deps.storage, auction_id.u128())?
+        .unwrap_or_default();
+    let (loser, losing_amount) = if amount > token_auction_state.high_bidder_amount {
+        let previous = (
+            token_auction_state.high_bidder_addr.clone(),
+            token_auction_state.high_bidder_amount,
+        );
+        token_auction_state.high_bidder_addr = info.sender.clone();
+        token_auction_state.high_bidder_amount = amount;
+        TOKEN_AUCTION_STATE.save(deps.storage, auction_id.u128(), &token_auction_state)?;
+        previous
+    } else {
+        (info.sender.clone(), amount)
+    };
+    if !losing_amount.is_zero() {
+        messages.extend(refund_messages(deps.storage, &loser, &asset, losing_amount)?);
+        SECOND_PRICES.save(deps.storage, auction_id.u128(), &second_price.max(losing_amount))?;
+    }
+
+    let mut bids_for_auction = BIDS.load(deps.storage, auction_id.u128())?;
+    bids_for_auction.push(Bid {
+        bidder: info.sender.to_string(),
+        amount,
+        timestamp: env.block.time,
+    });
+    BIDS.save(deps.storage, auction_id.u128(), &bids_for_auction)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "reveal_bid"),
+        attr("auction_id", auction_id.to_string()),
+        attr("bidder", info.sender),
+        attr("amount", amount.to_string()),
+    ]))
+}
+
+/// Returns every deposit of a sealed auction that has not been revealed,
+/// either to its bidder or, when forfeited, to the seller.
+fn unrevealed_deposit_messages(
+    storage: &mut dyn Storage,
+    token_auction_state: &TokenAuctionState,
+    forfeit: bool,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let unrevealed = SEALED_BIDS
+        .prefix(token_auction_state.auction_id.u128())
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, sealed_bid)) if sealed_bid.amount.is_some()))
+        .collect::<StdResult<Vec<(Addr, SealedBid)>>>()?;
+
+    let asset = &token_auction_state.asset;
+    if forfeit {
+        let total = unrevealed
+            .iter()
+            .fold(Uint128::zero(), |total, (_, sealed_bid)| total + sealed_bid.deposit);
+        if total.is_zero() {
+            return Ok(vec![]);
+        }
+        return Ok(vec![asset.transfer_msg(&token_auction_state.owner, total)?]);
+    }
+    let mut messages = vec![];
+    for (bidder, sealed_bid) in unrevealed {
+        messages.extend(refund_messages(storage, &bidder, asset, sealed_bid.deposit)?);
+    }
+    Ok(messages)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exec_relist(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+    start_time: u64,
+    duration: u64,
+    asset: AssetInfo,
+    min_bid: Option<Uint128>,
+    reserve_price: Option<Uint128>,
+    buy_now_price: Option<Uint128>,
+    extension_window: Option<u64>,
+    extension_duration: Option<u64>,
+    min_increment: Option<BidIncrement>,
+    accepted_assets: Option<Vec<AssetInfo>>,
+    allowlist: Option<BidderAllowlist>,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    ensure!(
+        info.sender == token_auction_state.owner,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionNotEnded {}
+    );
+    ensure!(
+        !token_auction_state.is_claimed,
+        ContractError::AuctionAlreadyClaimed {}
+    );
+    let reserve = RESERVE_PRICES
+        .may_load(deps.storage, auction_id.u128())?
+        .unwrap_or_default();
+    ensure!(
+        token_auction_state.high_bidder_amount.is_zero() || token_auction_state.high_bidder_amount < reserve,
+        ContractError::AuctionSold {}
+    );
+
+    // Close the old auction as claim does for unsold tokens, minus the transfer.
+    let mut messages = vec![];
+    if let AuctionType::Sealed {
+        forfeit_unrevealed, ..
+    } = token_auction_state.auction_type
+    {
+        messages.extend(unrevealed_deposit_messages(deps.storage, &token_auction_state, forfeit_unrevealed)?);
+    }
+    messages.extend(settle_high_bid(deps.storage, &token_auction_state, false)?);
+    token_auction_state.is_claimed = true;
+    TOKEN_AUCTION_STATE.save(deps.storage, auction_id.u128(), &token_auction_state)?;
+
+    // The collections may have been denied since the tokens were sent.
+    for item in auction_items(deps.storage, &token_auction_state)? {
+        ensure_collection_allowed(deps.storage, &Addr::unchecked(item.token_address))?;
+    }
+    let accepted_assets = accepted_assets.unwrap_or_default();
+    let bundle = BUNDLES.may_load(deps.storage, auction_id.u128())?;
+    if let Some(items) = &bundle {
+        ensure_bundle_assets_allowed(deps.storage, items, &asset, &accepted_assets)?;
+    }
+    let token_id = token_auction_state.token_id;
+    let token_address = token_auction_state.token_address;
+    let response = exec_start_auction(
+        deps.branch(),
+        env,
+        info.sender.to_string(),
+        token_id.clone(),
+        token_address.clone(),
+        start_time,
+        duration,
+        asset,
+        min_bid,
+        reserve_price,
+        buy_now_price,
+        extension_window,
+        extension_duration,
+        min_increment,
+        accepted_assets,
+        allowlist,
+    )?;
+    if let Some(items) = bundle {
+        let new_auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+        for item in &items[1..] {
+            record_auction_info(deps.storage, &item.token_address, &item.token_id, new_auction_id)?;
+        }
+        BUNDLES.save(deps.storage, new_auction_id.u128(), &items)?;
+    }
+
+    Ok(response
+        .add_messages(messages)
+        .add_attribute("relisted_auction_id", auction_id))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exec_update_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+    start_time: Option<u64>,
+    duration: Option<u64>,
+    asset: Option<AssetInfo>,
+    min_bid: Option<Uint128>,
+    reserve_price: Option<Uint128>,
+    buy_now_price: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    ensure!(
+        info.sender == token_auction_state.owner,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        !token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionEnded {}
+    );
+    ensure!(
+        token_auction_state.auction_type == AuctionType::English,
+        ContractError::NotEnglishAuction {}
+    );
+    ensure!(
+        token_auction_state.high_bidder_amount.is_zero(),
+        ContractError::AuctionHasBids {}
+    );
+
+    let min_bid = min_bid.or(token_auction_state.min_bid);
+    let reserve_price = reserve_price.or(RESERVE_PRICES.may_load(deps.storage, auction_id.u128())?);
+    let buy_now_price = buy_now_price.or(token_auction_state.buy_now_price);
+    validate_auction_prices(min_bid, reserve_price, buy_now_price)?;
+    if let Some(asset) = asset {
+        for item in auction_items(deps.storage, &token_auction_state)? {
+            ensure_asset_allowed(deps.storage, &item.token_address, &asset)?;
+        }
+        token_auction_state.asset = asset;
+    }
+
+    if start_time.is_some() || duration.is_some() {
+        let current_start = expiration_to_millisecond(&token_auction_state.start_time);
+        let current_duration = expiration_to_millisecond(&token_auction_state.end_time) - current_start;
+        let duration = duration.unwrap_or(current_duration);
+        let (start_expiration, end_expiration) = match start_time {
+            Some(start_time) => auction_window(&env, start_time, duration)?,
+            // Keeping the start, possibly already passed, only moves the end.
+            None => {
+                let end_time = current_start
+                    .checked_add(duration)
+                    .ok_or(ContractError::InvalidExpiration {})?;
+                let end_expiration = millisecond_to_expiration(end_time)?;
+                ensure!(
+                    duration > 0 && !end_expiration.is_expired(&env.block),
+                    ContractError::InvalidExpiration {}
+                );
+                (token_auction_state.start_time, end_expiration)
+            }
+        };
+        token_auction_state.start_time = start_expiration;
+        token_auction_state.end_time = end_expiration;
+    }
+
+    token_auction_state.min_bid = min_bid;
+    token_auction_state.buy_now_price = buy_now_price;
+    if let Some(reserve_price) = reserve_price {
+        RESERVE_PRICES.save(deps.storage, auction_id.u128(), &reserve_price)?;
+    }
+    TOKEN_AUCTION_STATE.save(deps.storage, auction_id.u128(), &token_auction_state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_auction"),
+        attr("start_time", token_auction_state.start_time.to_string()),
+        attr("end_time", token_auction_state.end_time.to_string()),
+        attr("coin_denom", token_auction_state.asset.to_string()),
+        attr("auction_id", auction_id.to_string()),
+    ]))
+}
+
+pub fn exec_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    ensure!(
+        info.sender == token_auction_state.owner,
+        ContractError::Unauthorized {}
+    );
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        !token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionEnded {}
+    );
+    let mut messages = nft_transfer_messages(
+        &auction_items(deps.storage, &token_auction_state)?,
+        info.sender.as_str(),
+    )?;
+
+    // Sealed auctions can only be cancelled before any bid is revealed.
+    if let AuctionType::Sealed { reveal_start, .. } = token_auction_state.auction_type {
+        ensure!(
+            !reveal_start.is_expired(&env.block),
+            ContractError::AuctionEnded {}
+        );
+        messages.extend(unrevealed_deposit_messages(deps.storage, &token_auction_state, false)?);
+    }
+
+    // Refund the highest bid; the other bidders retract theirs.
+    messages.extend(settle_high_bid(deps.storage, &token_auction_state, false)?);
+
+    token_auction_state.is_cancelled = true;
+    TOKEN_AUCTION_STATE.save(
+        deps.storage,
+        token_auction_state.auction_id.u128(),
+        &token_auction_state,
+    )?;
+
+    Ok(Response::new().add_messages(messages))
+}
+
+pub fn exec_claim(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    auction_id: Uint128,
+) -> Result<Response, ContractError> {
+    let mut token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    let token_id = token_auction_state.token_id.clone();
+    ensure!(
+        !token_auction_state.is_cancelled,
+        ContractError::AuctionCancelled {}
+    );
+    ensure!(
+        token_auction_state.end_time.is_expired(&env.block),
+        ContractError::AuctionNotEnded {}
+    );
+    ensure!(
+        !token_auction_state.is_claimed,
+        ContractError::AuctionAlreadyClaimed {}
+    );
+    let token_owner = owner_of_token(
+        deps.querier,
+        token_auction_state.token_address.clone(),
+        token_id.clone(),
+    )?.owner;
+    ensure!(
+        token_owner == env.contract.address,
+        ContractError::AuctionAlreadyClaimed {}
+    );
+
+    let mut response = Response::new();
+    if let AuctionType::Sealed {
+        pricing,
+        forfeit_unrevealed,
+        ..
+    } = &token_auction_state.auction_type
+    {
+        response = response.add_messages(unrevealed_deposit_messages(
+            deps.storage,
+            &token_auction_state,
+            *forfeit_unrevealed,
+        )?);
+        if *pricing == SealedPricing::SecondPrice && !token_auction_state.high_bidder_amount.is_zero() {
+            let price = SECOND_PRICES
+                .may_load(deps.storage, auction_id.u128())?
+                .unwrap_or_default()
+                .max(token_auction_state.min_bid.unwrap_or_default());
+            // Refund the part of the winning bid above the price paid.
+            if token_auction_state.high_bidder_amount > price {
+                response = response.add_messages(refund_messages(
+                    deps.storage,
+                    &token_auction_state.high_bidder_addr,
+                    &token_auction_state.asset,
+                    token_auction_state.high_bidder_amount - price,
+                )?);
+                token_auction_state.high_bidder_amount = price;
+            }
+        }
+    }
+
+    token_auction_state.is_claimed = true;
+    TOKEN_AUCTION_STATE.save(deps.storage, auction_id.u128(), &token_auction_state)?;
+    let items = auction_items(deps.storage, &token_auction_state)?;
+
+    if token_auction_state.high_bidder_addr.to_string().is_empty() || token_auction_state.high_bidder_amount.is_zero() {
+        return Ok(response
+            .add_messages(nft_transfer_messages(&items, &token_auction_state.owner)?)
+            .add_attribute("action", "claim")
+            .add_attribute("token_id", token_id)
+            .add_attribute("token_contract", token_auction_state.token_address)
+            .add_attribute("recipient", token_auction_state.owner)
+            .add_attribute("winning_bid_amount", token_auction_state.high_bidder_amount)
+            .add_attribute("auction_id", token_auction_state.auction_id));
+    }
+
+    let reserve_price = RESERVE_PRICES
+        .may_load(deps.storage, token_auction_state.auction_id.u128())?
+        .unwrap_or_default();
+    if token_auction_state.high_bidder_amount < reserve_price {
+        return Ok(response
+            // Return NFT to the original owner.
+            .add_messages(nft_transfer_messages(&items, &token_auction_state.owner)?)
+            // Refund the highest bid; the other bidders retract theirs.
+            .add_messages(settle_high_bid(deps.storage, &token_auction_state, false)?)
+            .add_attribute("action", "claim")
+            .add_attribute("token_id", token_id)
+            .add_attribute("token_contract", token_auction_state.token_address)
+            .add_attribute("recipient", token_auction_state.owner)
+            .add_attribute("reserve_met", "false")
+            .add_attribute("auction_id", token_auction_state.auction_id));
+    }
+
+    // The winner pays in the asset they bid with.
+    let payment = winning_payment(&token_auction_state);
+    Ok(response
+        .add_messages(settle_high_bid(deps.storage, &token_auction_state, true)?)
+        .add_messages(bundle_settlement_messages(
+            deps.as_ref(),
+            &items,
+            &token_auction_state.owner,
+            token_auction_state.high_bidder_addr.as_str(),
+            payment.amount,
+            &payment.info,
+        )?)
+        .add_attribute("action", "claim")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", token_auction_state.token_address)
+        .add_attribute("recipient", &token_auction_state.high_bidder_addr)
+        .add_attribute("winning_bid_amount", token_auction_state.high_bidder_amount)
+        .add_attribute("auction_id", token_auction_state.auction_id))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn exec_list_for_sale(
+    deps: DepsMut,
+    env: Env,
+    seller: String,
+    token_id: String,
+    token_address: String,
+    price: Uint128,
+    denom: String,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure!(!price.is_zero(), ContractError::InvalidPrice {});
+    ensure_asset_allowed(
+        deps.storage,
+        &token_address,
+        &AssetInfo::Native {
+            denom: denom.clone(),
+        },
+    )?;
+
+    let expires = optional_millisecond_to_expiration(expires)?;
+    ensure!(
+        !expires.is_expired(&env.block),
+        ContractError::InvalidExpiration {}
+    );
+
+    listings().save(
+        deps.storage,
+        (&token_address, &token_id),
+        &Listing {
+            token_id: token_id.clone(),
+            token_address: token_address.clone(),
+            seller: seller.clone(),
+            price,
+            denom: denom.clone(),
+            expires,
+        },
+    )?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "list_for_sale"),
+        attr("token_id", token_id),
+        attr("token_contract", token_address),
+        attr("seller", seller),
+        attr("price", price.to_string()),
+        attr("denom", denom),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+pub fn exec_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    token_address: String,
+) -> Result<Response, ContractError> {
+    let listing = get_listing(deps.storage, &token_id, &token_address)?;
+    ensure!(
+        !listing.expires.is_expired(&env.block),
+        ContractError::ListingExpired {}
+    );
+    ensure!(
+        listing.seller != info.sender,
+        ContractError::SellerCannotBuy {}
+    );
+    ensure!(
+        info.funds.len() == 1
+            && info.funds[0].denom == listing.denom
+            && info.funds[0].amount == listing.price,
+        ContractError::InvalidFunds {
+            msg: format!("Exactly {}{} must be sent to buy", listing.price, listing.denom),
+        }
+    );
+
+    listings().remove(deps.storage, (&token_address, &token_id))?;
+
+    Ok(Response::new()
+        .add_messages(settlement_messages(
+            deps.as_ref(),
+            &listing.token_address,
+            &token_id,
+            &listing.seller,
+            info.sender.as_str(),
+            listing.price,
+            &AssetInfo::Native {
+                denom: listing.denom.clone(),
+            },
+        )?)
+        .add_attribute("action", "buy")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", listing.token_address)
+        .add_attribute("recipient", info.sender)
+        .add_attribute("price", listing.price))
+}
+
+pub fn exec_update_price(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    token_address: String,
+    price: Uint128,
+) -> Result<Response, ContractError> {
+    let mut listing = get_listing(deps.storage, &token_id, &token_address)?;
+    ensure!(
+        listing.seller == info.sender,
+        ContractError::Unauthorized {}
+    );
+    ensure!(!price.is_zero(), ContractError::InvalidPrice {});
+
+    listing.price = price;
+    listings().save(deps.storage, (&token_address, &token_id), &listing)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_price"),
+        attr("token_id", token_id),
+        attr("token_contract", token_address),
+        attr("price", price.to_string()),
+    ]))
+}
+
+pub fn exec_delist(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    token_address: String,
+) -> Result<Response, ContractError> {
+    let listing = get_listing(deps.storage, &token_id, &token_address)?;
+    ensure!(
+        listing.seller == info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    listings().remove(deps.storage, (&token_address, &token_id))?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: listing.token_address.clone(),
+            msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                recipient: listing.seller,
+                token_id: token_id.clone(),
+            })?,
+            funds: vec![],
+        }))
+        .add_attribute("action", "delist")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", listing.token_address))
+}
+
+pub fn exec_make_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_address: String,
+    token_id: String,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    make_offer(deps, env, info.sender, token_address, token_id, expires, payment)
+}
+
+fn make_offer(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    token_address: String,
+    token_id: String,
+    expires: Option<u64>,
+    payment: Asset,
+) -> Result<Response, ContractError> {
+    ensure!(
+        !payment.amount.is_zero(),
+        ContractError::InvalidFunds {
+            msg: "Offers require a non-zero amount.".to_string(),
+        }
+    );
+    ensure_collection_allowed(deps.storage, &deps.api.addr_validate(&token_address)?)?;
+    ensure_asset_allowed(deps.storage, &token_address, &payment.info)?;
+    let expires = optional_millisecond_to_expiration(expires)?;
+    ensure!(
+        !expires.is_expired(&env.block),
+        ContractError::InvalidExpiration {}
+    );
+
+    let key = (token_address.as_str(), token_id.as_str(), bidder.as_str());
+    ensure!(
+        !offers().has(deps.storage, key),
+        ContractError::OfferAlreadyExists {}
+    );
+    offers().save(
+        deps.storage,
+        key,
+        &Offer {
+            token_address: token_address.clone(),
+            token_id: token_id.clone(),
+            bidder: bidder.to_string(),
+            asset: payment.clone(),
+            expires,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "make_offer"),
+        attr("token_id", token_id),
+        attr("token_contract", token_address),
+        attr("bidder", bidder),
+        attr("amount", payment.amount.to_string()),
+        attr("asset", payment.info.to_string()),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+pub fn exec_cancel_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_address: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let offer = get_offer(deps.storage, &token_address, &token_id, info.sender.as_str())?;
+    offers().remove(deps.storage, (&token_address, &token_id, info.sender.as_str()))?;
+
+    Ok(Response::new()
+        .add_message(offer.asset.info.transfer_msg(&offer.bidder, offer.asset.amount)?)
+        .add_attribute("action", "cancel_offer")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", token_address)
+        .add_attribute("bidder", offer.bidder))
+}
+
+fn exec_accept_offer(
+    deps: DepsMut,
+    env: Env,
+    seller: String,
+    token_id: String,
+    token_address: String,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    let offer = get_offer(deps.storage, &token_address, &token_id, &bidder)?;
+    ensure!(
+        !offer.expires.is_expired(&env.block),
+        ContractError::OfferExpired {}
+    );
+    ensure!(offer.bidder != seller, ContractError::SellerCannotBuy {});
+
+    offers().remove(deps.storage, (&token_address, &token_id, &bidder))?;
+
+    Ok(Response::new()
+        .add_messages(settlement_messages(
+            deps.as_ref(),
+            &token_address,
+            &token_id,
+            &seller,
+            &offer.bidder,
+            offer.asset.amount,
+            &offer.asset.info,
+        )?)
+        .add_attribute("action", "accept_offer")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", token_address)
+        .add_attribute("seller", seller)
+        .add_attribute("bidder", offer.bidder)
+        .add_attribute("amount", offer.asset.amount))
+}
+
+pub fn exec_make_collection_offer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_address: String,
+    price: Uint128,
+    quantity: u32,
+    expires: Option<u64>,
+) -> Result<Response, ContractError> {
+    let payment = native_payment(&info)?;
+    make_collection_offer(deps, env, info.sender, token_address, price, quantity, expires, payment)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn make_collection_offer(
+    deps: DepsMut,
+    env: Env,
+    bidder: Addr,
+    token_address: String,
+    price: Uint128,
+    quantity: u32,
+    expires: Option<u64>,
+    payment: Asset,
+) -> Result<Response, ContractError> {
+    ensure!(!price.is_zero(), ContractError::InvalidPrice {});
+    ensure!(quantity > 0, ContractError::InvalidQuantity {});
+    let total = price.checked_mul(Uint128::from(quantity))?;
+    ensure!(
+        payment.amount == total,
+        ContractError::InvalidFunds {
+            msg: format!("Exactly {}{} must be sent for the offer", total, payment.info),
+        }
+    );
+    ensure_collection_allowed(deps.storage, &deps.api.addr_validate(&token_address)?)?;
+    ensure_asset_allowed(deps.storage, &token_address, &payment.info)?;
+    let expires = optional_millisecond_to_expiration(expires)?;
+    ensure!(
+        !expires.is_expired(&env.block),
+        ContractError::InvalidExpiration {}
+    );
+
+    let key = (token_address.as_str(), bidder.as_str());
+    ensure!(
+        !collection_offers().has(deps.storage, key),
+        ContractError::OfferAlreadyExists {}
+    );
+    collection_offers().save(
+        deps.storage,
+        key,
+        &CollectionOffer {
+            token_address: token_address.clone(),
+            bidder: bidder.to_string(),
+            asset: payment.info.clone(),
+            price,
+            quantity,
+            expires,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "make_collection_offer"),
+        attr("token_contract", token_address),
+        attr("bidder", bidder),
+        attr("price", price.to_string()),
+        attr("quantity", quantity.to_string()),
+        attr("asset", payment.info.to_string()),
+        attr("expires", expires.to_string()),
+    ]))
+}
+
+pub fn exec_cancel_collection_offer(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_address: String,
+) -> Result<Response, ContractError> {
+    let offer = get_collection_offer(deps.storage, &token_address, info.sender.as_str())?;
+    collection_offers().remove(deps.storage, (&token_address, info.sender.as_str()))?;
+
+    let refund = offer.price.checked_mul(Uint128::from(offer.quantity))?;
+    Ok(Response::new()
+        .add_message(offer.asset.transfer_msg(&offer.bidder, refund)?)
+        .add_attribute("action", "cancel_collection_offer")
+        .add_attribute("token_contract", token_address)
+        .add_attribute("bidder", offer.bidder))
+}
+
+fn exec_accept_collection_offer(
+    deps: DepsMut,
+    env: Env,
+    seller: String,
+    token_id: String,
+    token_address: String,
+    bidder: String,
+) -> Result<Response, ContractError> {
+    let mut offer = get_collection_offer(deps.storage, &token_address, &bidder)?;
+    ensure!(
+        !offer.expires.is_expired(&env.block),
+        ContractError::OfferExpired {}
+    );
+    ensure!(offer.bidder != seller, ContractError::SellerCannotBuy {});
+
+    offer.quantity -= 1;
+    if offer.quantity == 0 {
+        collection_offers().remove(deps.storage, (&token_address, &bidder))?;
+    } else {
+        collection_offers().save(deps.storage, (&token_address, &bidder), &offer)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(settlement_messages(
+            deps.as_ref(),
+            &token_address,
+            &token_id,
+            &seller,
+            &offer.bidder,
+            offer.price,
+            &offer.asset,
+        )?)
+        .add_attribute("action", "accept_collection_offer")
+        .add_attribute("token_id", token_id)
+        .add_attribute("token_contract", token_address)
+        .add_attribute("seller", seller)
+        .add_attribute("bidder", offer.bidder)
+        .add_attribute("price", offer.price)
+        .add_attribute("remaining_quantity", offer.quantity.to_string()))
+}
+
+pub fn exec_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let key = asset.key();
+    let mut balance = BALANCES
+        .may_load(deps.storage, (&info.sender, &key))?
+        .map(|balance| balance.amount)
+        .unwrap_or_default();
+    ensure!(
+        !amount.is_zero() && amount <= balance,
+        ContractError::InsufficientBalance { balance }
+    );
+    balance -= amount;
+    if balance.is_zero() {
+        BALANCES.remove(deps.storage, (&info.sender, &key));
+    } else {
+        BALANCES.save(
+            deps.storage,
+            (&info.sender, &key),
+            &Asset {
+                info: asset.clone(),
+                amount: balance,
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_message(asset.transfer_msg(info.sender.as_str(), amount)?)
+        .add_attribute("action", "withdraw")
+        .add_attribute("recipient", info.sender)
+        .add_attribute("asset", asset.to_string())
+        .add_attribute("amount", amount))
+}
+
+pub fn exec_set_auto_send(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if enabled {
+        AUTO_SEND.save(deps.storage, &info.sender, &true)?;
+    } else {
+        AUTO_SEND.remove(deps.storage, &info.sender);
+    }
+    Ok(Response::new()
+        .add_attribute("action", "set_auto_send")
+        .add_attribute("address", info.sender)
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn exec_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+    fee_collector: Option<String>,
+    fee_bps: Option<u64>,
+    allowed_assets: Option<Vec<AssetInfo>>,
+    restrict_collections: Option<bool>,
+    default_min_increment: Option<BidIncrement>,
+    price_source: Option<PriceSource>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == config.admin, ContractError::Unauthorized {});
+
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_validate(&admin)?;
+    }
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+    }
+    if let Some(fee_bps) = fee_bps {
+        ensure!(fee_bps <= MAX_FEE_BPS, ContractError::InvalidFeeBps {});
+        config.fee_bps = fee_bps;
+    }
+    if let Some(allowed_assets) = allowed_assets {
+        config.allowed_assets = allowed_assets;
+    }
+    if let Some(restrict_collections) = restrict_collections {
+        config.restrict_collections = restrict_collections;
+    }
+    if let Some(default_min_increment) = default_min_increment {
+        config.default_min_increment = Some(default_min_increment);
+    }
+    if let Some(price_source) = price_source {
+        if let PriceSource::Oracle { address } = &price_source {
+            deps.api.addr_validate(address.as_str())?;
+        }
+        config.price_source = price_source;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_config"),
+        attr("admin", config.admin),
+        attr("fee_collector", config.fee_collector),
+        attr("fee_bps", config.fee_bps.to_string()),
+    ]))
+}
+
+pub fn exec_set_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+    reference: AssetInfo,
+    rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == config.admin, ContractError::Unauthorized {});
+
+    let (asset_key, reference_key) = (asset.key(), reference.key());
+    match rate {
+        Some(rate) => {
+            ensure!(!rate.is_zero(), ContractError::InvalidPrice {});
+            RATES.save(deps.storage, (&asset_key, &reference_key), &rate)?;
+        }
+        None => RATES.remove(deps.storage, (&asset_key, &reference_key)),
+    }
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_rate"),
+        attr("asset", asset.to_string()),
+        attr("reference", reference.to_string()),
+        attr("rate", rate.map_or("none".to_string(), |rate| rate.to_string())),
+    ]))
+}
+
+pub fn exec_add_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    status: CollectionStatus,
+    name: Option<String>,
+    fee_bps: Option<u64>,
+    allowed_assets: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == config.admin, ContractError::Unauthorized {});
+    if let Some(fee_bps) = fee_bps {
+        ensure!(fee_bps <= MAX_FEE_BPS, ContractError::InvalidFeeBps {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    let collection = CollectionInfo {
+        address: address.clone(),
+        status,
+        name,
+        fee_bps,
+        allowed_assets,
+    };
+    COLLECTIONS.save(deps.storage, &address, &collection)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_collection"),
+        attr("address", address),
+        attr("status", format!("{:?}", collection.status)),
+    ]))
+}
+
+pub fn exec_remove_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(info.sender == config.admin, ContractError::Unauthorized {});
+
+    let address = deps.api.addr_validate(&address)?;
+    ensure!(
+        COLLECTIONS.has(deps.storage, &address),
+        ContractError::CollectionDoesNotExist {}
+    );
+    COLLECTIONS.remove(deps.storage, &address);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_collection"),
+        attr("address", address),
+    ]))
+}
+
+// ============================== migrations ==============================//
+/// A state migration for storage written by contract versions below `version`.
+pub struct Migration {
+    pub version: &'static str,
+    pub run: fn(DepsMut, &Env, &MigrateMsg) -> Result<Vec<Attribute>, ContractError>,
+}
+
+/// Every state migration, oldest first. Add a step here whenever a release
+/// changes a storage layout in a way serde defaults can't absorb.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: "0.2.0",
+        run: migrate_auction_states,
+    },
+    Migration {
+        version: "0.2.0",
+        run: migrate_auction_infos,
+    },
+    Migration {
+        version: "0.3.0",
+        run: migrate_collections,
+    },
+    Migration {
+        version: "0.4.0",
+        run: migrate_committed_bids,
+    },
+];
+
+/// Runs the migrations newer than `from` in order and collects their attributes.
+pub fn run_migrations(
+    mut deps: DepsMut,
+    env: &Env,
+    from: &Version,
+    msg: &MigrateMsg,
+) -> Result<Vec<Attribute>, ContractError> {
+    let mut attributes = vec![];
+    for migration in MIGRATIONS.iter() {
+        if *from < Version::parse(migration.version)? {
+            attributes.extend((migration.run)(deps.branch(), env, msg)?);
+        }
+    }
+    Ok(attributes)
+}
+
+/// Rewrites auction states priced in a `coin_denom` to the `asset` layout.
+/// Those builds had no claim flag and relied on the contract no longer
+/// holding the token, so auctions that were settled or relisted since are
+/// marked claimed.
+fn migrate_auction_states(deps: DepsMut, env: &Env, _msg: &MigrateMsg) -> Result<Vec<Attribute>, ContractError> {
+    // Typed keys would also parse the values, which fails on the old layout.
+    let auction_ids = TOKEN_AUCTION_STATE
+        .keys_raw(deps.storage, None, None, Order::Ascending)
+        .map(|key| {
+            key.try_into()
+                .map(u128::from_be_bytes)
+                .map_err(|_| StdError::generic_err("Invalid auction id key"))
+        })
+        .collect::<StdResult<Vec<u128>>>()?;
+    let mut legacy_states = vec![];
+    for auction_id in auction_ids {
+        // Unversioned builds after the asset change already use the new layout.
+        if TOKEN_AUCTION_STATE.load(deps.storage, auction_id).is_ok() {
+            continue;
+        }
+        legacy_states.push((auction_id, LEGACY_TOKEN_AUCTION_STATE.load(deps.storage, auction_id)?));
+    }
+
+    // Ids only grow, so the last one seen for a token is its latest auction.
+    let mut latest_ids = HashMap::new();
+    for (auction_id, legacy) in legacy_states.iter() {
+        latest_ids.insert((legacy.token_address.clone(), legacy.token_id.clone()), *auction_id);
+    }
+
+    let migrated = legacy_states.len();
+    for (auction_id, legacy) in legacy_states {
+        let is_latest = latest_ids.get(&(legacy.token_address.clone(), legacy.token_id.clone())) == Some(&auction_id);
+        let is_claimed = !is_latest
+            || owner_of_token(deps.querier, legacy.token_address.clone(), legacy.token_id.clone())
+                .is_ok_and(|res| res.owner != env.contract.address);
+        TOKEN_AUCTION_STATE.save(
+            deps.storage,
+            auction_id,
+            &TokenAuctionState {
+                start_time: legacy.start_time,
+                end_time: legacy.end_time,
+                high_bidder_addr: legacy.high_bidder_addr,
+                high_bidder_amount: legacy.high_bidder_amount,
+                asset: AssetInfo::Native {
+                    denom: legacy.coin_denom,
+                },
+                auction_id: legacy.auction_id,
+                min_bid: legacy.min_bid,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                owner: legacy.owner,
+                token_id: legacy.token_id,
+                token_address: legacy.token_address,
+                is_cancelled: legacy.is_cancelled,
+                is_claimed,
+                proxy_max: None,
+                accepted_assets: vec![],
+                high_bid_asset: None,
+                auction_type: AuctionType::English,
+            },
+        )?;
+    }
+    Ok(vec![attr("migrated_auction_states", migrated.to_string())])
+}
+
+/// Rebuilds auction infos under the `(token_address, token_id)` key. The
+/// concatenated string key could map two tokens onto one entry, so the ids
+/// are taken from the auction states rather than the legacy entries.
+fn migrate_auction_infos(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<Vec<Attribute>, ContractError> {
+    let storage = deps.storage;
+    let legacy_keys = legacy_auction_infos()
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    for key in legacy_keys.iter() {
+        legacy_auction_infos().remove(storage, key)?;
+    }
+
+    let auction_states = TOKEN_AUCTION_STATE
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u128, TokenAuctionState)>>>()?;
+    let mut migrated = 0;
+    for (auction_id, state) in auction_states {
+        let token_addr = Addr::unchecked(&state.token_address);
+        let key = (&token_addr, state.token_id.as_str());
+        let mut auction_info = match auction_infos().may_load(storage, key)? {
+            Some(auction_info) => auction_info,
+            None => {
+                migrated += 1;
+                AuctionInfo {
+                    auction_ids: vec![],
+                    token_address: state.token_address.clone(),
+                    token_id: state.token_id.clone(),
+                }
+            }
+        };
+        let auction_id = Uint128::from(auction_id);
+        if !auction_info.auction_ids.contains(&auction_id) {
+            auction_info.push(auction_id);
+            auction_infos().save(storage, key, &auction_info)?;
+        }
+    }
+    Ok(vec![attr("migrated_auction_infos", migrated.to_string())])
+}
+
+/// Moves the config's collection allow list into `COLLECTIONS`. Deployments
+/// that never had a config get one from the migrate message.
+fn migrate_collections(deps: DepsMut, _env: &Env, msg: &MigrateMsg) -> Result<Vec<Attribute>, ContractError> {
+    let legacy = match LEGACY_CONFIG.may_load(deps.storage)? {
+        Some(legacy) => legacy,
+        None => seed_legacy_config(deps.api, msg)?,
+    };
+    let storage = deps.storage;
+    for address in legacy.allowed_collections.iter() {
+        COLLECTIONS.save(
+            storage,
+            address,
+            &CollectionInfo {
+                address: address.clone(),
+                status: CollectionStatus::Allowed,
+                name: None,
+                fee_bps: None,
+                allowed_assets: None,
+            },
+        )?;
+    }
+    CONFIG.save(
+        storage,
+        &Config {
+            admin: legacy.admin,
+            fee_collector: legacy.fee_collector,
+            fee_bps: legacy.fee_bps,
+            allowed_assets: legacy.allowed_assets,
+            restrict_collections: !legacy.allowed_collections.is_empty(),
+            default_min_increment: legacy.default_min_increment,
+            price_source: PriceSource::default(),
+        },
+    )?;
+    Ok(vec![attr(
+        "migrated_collections",
+        legacy.allowed_collections.len().to_string(),
+    )])
+}
+
+fn seed_legacy_config(api: &dyn Api, msg: &MigrateMsg) -> Result<LegacyConfig, ContractError> {
+    let admin = api.addr_validate(msg.admin.as_deref().ok_or(ContractError::MigrationConfigRequired {})?)?;
+    let fee_collector = match &msg.fee_collector {
+        Some(fee_collector) => api.addr_validate(fee_collector)?,
+        None => admin.clone(),
+    };
+    let fee_bps = msg.fee_bps.unwrap_or_default();
+    ensure!(fee_bps <= MAX_FEE_BPS, ContractError::InvalidFeeBps {});
+    Ok(LegacyConfig {
+        admin,
+        fee_collector,
+        fee_bps,
+        allowed_assets: vec![],
+        allowed_collections: vec![],
+        default_min_increment: None,
+    })
+}
+
+/// Records the escrowed high bid of every auction not yet settled as
+/// committed, so it can be raised, retracted once outbid or settled at
+/// claim. Settled auctions, baseline ones included once
+/// `migrate_auction_states` marks them claimed, hold no funds and are skipped.
+fn migrate_committed_bids(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<Vec<Attribute>, ContractError> {
+    let storage = deps.storage;
+    let open_auctions = TOKEN_AUCTION_STATE
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| {
+            matches!(item, Ok((_, state)) if !state.is_claimed
+                && !state.is_cancelled
+                && !state.high_bidder_amount.is_zero()
+                && state.auction_type == AuctionType::English)
+        })
+        .collect::<StdResult<Vec<(u128, TokenAuctionState)>>>()?;
+    for (auction_id, state) in open_auctions.iter() {
+        COMMITTED_BIDS.save(
+            storage,
+            (*auction_id, &state.high_bidder_addr),
+            &state.high_bidder_amount,
+        )?;
+    }
+    Ok(vec![attr("migrated_committed_bids", open_auctions.len().to_string())])
+}
+
+// ============================== query handlers ==============================//
+pub fn query_config(deps: Deps) -> Result<Config, ContractError> {
+    Ok(CONFIG.load(deps.storage)?)
+}
+
+pub fn query_balance(deps: Deps, address: String) -> Result<Vec<Asset>, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    BALANCES
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+pub fn query_bidder_allowlist(deps: Deps, auction_id: Uint128) -> Result<Option<BidderAllowlist>, ContractError> {
+    Ok(BIDDER_ALLOWLISTS.may_load(deps.storage, auction_id.u128())?)
+}
+
+pub fn query_rate(deps: Deps, asset: AssetInfo, reference: AssetInfo) -> Result<Decimal, ContractError> {
+    price_rate(deps, &asset, &reference)
+}
+
+pub fn query_collection(deps: Deps, address: String) -> Result<CollectionInfo, ContractError> {
+    COLLECTIONS
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .ok_or(ContractError::CollectionDoesNotExist {})
+}
+
+pub fn query_collections(
+    deps: Deps,
+    status: Option<CollectionStatus>,
+    start_after: Option<String>,
+    limit: Option<u64>,
+) -> Result<Vec<CollectionInfo>, ContractError> {
+    read_collections(deps.storage, status, start_after, limit)
+}
+
+pub fn query_offer(
+    deps: Deps,
+    token_address: String,
+    token_id: String,
+    bidder: String,
+) -> Result<Offer, ContractError> {
+    get_offer(deps.storage, &token_address, &token_id, &bidder)
+}
+
+pub fn query_offers(
+    deps: Deps,
+    token_address: Option<String>,
+    token_id: Option<String>,
+    bidder: Option<String>,
+    start_after: Option<(String, String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<Offer>, ContractError> {
+    read_offers(deps.storage, token_address, token_id, bidder, start_after, limit)
+}
+
+pub fn query_collection_offer(
+    deps: Deps,
+    token_address: String,
+    bidder: String,
+) -> Result<CollectionOffer, ContractError> {
+    get_collection_offer(deps.storage, &token_address, &bidder)
+}
+
+pub fn query_collection_offers(
+    deps: Deps,
+    token_address: Option<String>,
+    bidder: Option<String>,
+    start_after: Option<(String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<CollectionOffer>, ContractError> {
+    read_collection_offers(deps.storage, token_address, bidder, start_after, limit)
+}
+
+pub fn query_current_price(deps: Deps, env: Env, auction_id: Uint128) -> Result<Uint128, ContractError> {
+    let token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    dutch_price(&token_auction_state, &env.block).ok_or(ContractError::NotDutchAuction {})
+}
+
+pub fn query_auction_infos(
+    deps: Deps,
+    token_address: Option<String>,
+    start_after: Option<(String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<AuctionInfo>, ContractError> {
+    read_auction_infos(deps.storage, token_address, start_after, limit)
+}
+
+
+pub fn query_bundle(deps: Deps, auction_id: Uint128) -> Result<Vec<BundleItem>, ContractError> {
+    let token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    Ok(auction_items(deps.storage, &token_auction_state)?)
+}
+
+pub fn query_bundle_draft(deps: Deps, seller: String) -> Result<Vec<BundleItem>, ContractError> {
+    let seller = deps.api.addr_validate(&seller)?;
+    Ok(BUNDLE_DRAFTS.may_load(deps.storage, &seller)?.unwrap_or_default())
+}
+
+pub fn query_committed_bid(deps: Deps, auction_id: Uint128, bidder: String) -> Result<Uint128, ContractError> {
+    let bidder = deps.api.addr_validate(&bidder)?;
+    let committed = COMMITTED_BIDS
+        .may_load(deps.storage, (auction_id.u128(), &bidder))?
+        .unwrap_or_default();
+    // The leading proxy bid shows the price it reached, not its maximum.
+    if let Some(token_auction_state) = TOKEN_AUCTION_STATE.may_load(deps.storage, auction_id.u128())? {
+        if !committed.is_zero()
+            && token_auction_state.proxy_max.is_some()
+            && token_auction_state.high_bidder_addr == bidder
+        {
+            return Ok(token_auction_state.high_bidder_amount);
+        }
+    }
+    Ok(committed)
+}
 
 pub fn query_bids(
     deps: Deps,
+    env: Env,
     auction_id: Uint128,
     start_after: Option<u64>,
     limit: Option<u64>,
     order_by: Option<OrderBy>,
 ) -> Result<Vec<Bid>, ContractError> {
+    let token_auction_state = get_auction_state(deps.storage, auction_id)?;
+    // Sealed bids stay hidden until the reveal phase is over.
+    if matches!(token_auction_state.auction_type, AuctionType::Sealed { .. }) {
+        ensure!(
+            token_auction_state.end_time.is_expired(&env.block),
+            ContractError::BidsSealed {}
+        );
+    }
     let bids = read_bids(
         deps.storage,
         auction_id.u128(),
@@ -333,10 +2468,30 @@ pub fn query_auction_state(
     deps: Deps,
     auction_id: Uint128,
 ) -> Result<TokenAuctionState, ContractError> {
-    let token_auction_state = TOKEN_AUCTION_STATE.load(deps.storage, auction_id.u128())?;
+    let mut token_auction_state = TOKEN_AUCTION_STATE.load(deps.storage, auction_id.u128())?;
+    // The leader's maximum stays hidden from other bidders.
+    token_auction_state.proxy_max = None;
     Ok(token_auction_state)
 }
 
+pub fn query_listing(
+    deps: Deps,
+    token_id: String,
+    token_address: String,
+) -> Result<Listing, ContractError> {
+    get_listing(deps.storage, &token_id, &token_address)
+}
+
+pub fn query_listings(
+    deps: Deps,
+    token_address: Option<String>,
+    seller: Option<String>,
+    start_after: Option<(String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<Listing>, ContractError> {
+    read_listings(deps.storage, token_address, seller, start_after, limit)
+}
+
 // ============================== helper functions ==============================//
 fn get_and_increment_next_auction_id(
     storage: &mut dyn Storage,
@@ -360,6 +2515,50 @@ fn millisecond_to_expiration(time:u64) -> Result<Expiration, ContractError> {
     )))
 }
 
+fn expiration_to_millisecond(expiration: &Expiration) -> u64 {
+    match expiration {
+        Expiration::AtTime(time) => time.nanos() / 1000000,
+        _ => 0,
+    }
+}
+
+/// The price a Dutch auction sells at on `block`, or `None` for English auctions.
+pub fn dutch_price(state: &TokenAuctionState, block: &BlockInfo) -> Option<Uint128> {
+    let (start_price, end_price, decay) = match &state.auction_type {
+        AuctionType::English | AuctionType::Sealed { .. } => return None,
+        AuctionType::Dutch {
+            start_price,
+            end_price,
+            decay,
+        } => (*start_price, *end_price, decay),
+    };
+    let (start, end) = match (state.start_time, state.end_time) {
+        (Expiration::AtTime(start), Expiration::AtTime(end)) => (start.nanos(), end.nanos()),
+        _ => return Some(start_price),
+    };
+    let duration = end.saturating_sub(start);
+    if duration == 0 {
+        return Some(end_price);
+    }
+    let elapsed = block.time.nanos().saturating_sub(start).min(duration);
+    let elapsed = match decay {
+        PriceDecay::Linear => elapsed,
+        PriceDecay::Stepwise { interval } => {
+            let interval = interval * 1000000;
+            elapsed / interval * interval
+        }
+    };
+    Some(start_price - (start_price - end_price).multiply_ratio(elapsed, duration))
+}
+
+/// `Never` when no time is given.
+fn optional_millisecond_to_expiration(time: Option<u64>) -> Result<Expiration, ContractError> {
+    match time {
+        None => Ok(Expiration::Never {}),
+        Some(time) => millisecond_to_expiration(time),
+    }
+}
+
 fn block_to_expiration(block: &BlockInfo, model: Expiration) -> Option<Expiration> {
     match model {
         Expiration::AtTime(_) => Some(Expiration::AtTime(block.time)),
@@ -368,20 +2567,362 @@ fn block_to_expiration(block: &BlockInfo, model: Expiration) -> Option<Expiratio
     }
 }
 
-fn get_token_auction_state(
+/// Smallest bid beating `amount`: one minimum increment above it, or one unit
+/// when the auction has no increment.
+fn outbid_amount(token_auction_state: &TokenAuctionState, amount: Uint128) -> Uint128 {
+    let increment = token_auction_state.min_increment.as_ref().map_or(Uint128::zero(), |increment| {
+        increment.amount.max(amount.multiply_ratio(increment.bps, MAX_FEE_BPS))
+    });
+    amount + increment.max(Uint128::one())
+}
+
+/// Returns the smallest amount the next bid has to reach.
+fn min_next_bid(token_auction_state: &TokenAuctionState) -> Uint128 {
+    let high_bid = token_auction_state.high_bidder_amount;
+    match &token_auction_state.min_increment {
+        Some(_) if !high_bid.is_zero() => outbid_amount(token_auction_state, high_bid),
+        _ => high_bid,
+    }
+}
+
+/// Returns the new end time if a bid placed now falls inside the auction's
+/// extension window.
+fn extended_end_time(token_auction_state: &TokenAuctionState, block: &BlockInfo) -> Option<Expiration> {
+    let (window, duration) = match (
+        token_auction_state.extension_window,
+        token_auction_state.extension_duration,
+    ) {
+        (Some(window), Some(duration)) => (window * 1000000, duration * 1000000),
+        _ => return None,
+    };
+    match token_auction_state.end_time {
+        Expiration::AtTime(end_time) if end_time.nanos() <= block.time.nanos().saturating_add(window) => {
+            let extended = Timestamp::from_nanos(block.time.nanos().saturating_add(duration));
+            (extended > end_time).then_some(Expiration::AtTime(extended))
+        }
+        _ => None,
+    }
+}
+
+/// Resolves the most recent auction of a token, for the token-addressed messages.
+pub fn latest_auction_id(
     storage: &dyn Storage,
     token_id: &str,
     token_address: &str,
+) -> Result<Uint128, ContractError> {
+    match auction_infos().may_load(storage, (&Addr::unchecked(token_address), token_id))? {
+        None => Err(ContractError::AuctionDoesNotExist {}),
+        Some(auction_info) => Ok(*auction_info.latest().unwrap()),
+    }
+}
+
+fn get_auction_state(
+    storage: &dyn Storage,
+    auction_id: Uint128,
 ) -> Result<TokenAuctionState, ContractError> {
-    let key = token_id.to_owned() + token_address;
-    let latest_auction_id: Uint128 = match auction_infos().may_load(storage, &key)? {
-        None => return Err(ContractError::AuctionDoesNotExist {}),
-        Some(auction_info) => *auction_info.latest().unwrap(),
+    TOKEN_AUCTION_STATE
+        .may_load(storage, auction_id.u128())?
+        .ok_or(ContractError::AuctionDoesNotExist {})
+}
+
+fn native_payment(info: &MessageInfo) -> Result<Asset, ContractError> {
+    ensure!(
+        info.funds.len() == 1,
+        ContractError::InvalidFunds {
+            msg: "Auctions require exactly one coin to be sent.".to_string(),
+        }
+    );
+    Ok(Asset {
+        info: AssetInfo::Native {
+            denom: info.funds[0].denom.clone(),
+        },
+        amount: info.funds[0].amount,
+    })
+}
+
+fn get_offer(
+    storage: &dyn Storage,
+    token_address: &str,
+    token_id: &str,
+    bidder: &str,
+) -> Result<Offer, ContractError> {
+    offers()
+        .may_load(storage, (token_address, token_id, bidder))?
+        .ok_or(ContractError::OfferDoesNotExist {})
+}
+
+fn get_collection_offer(
+    storage: &dyn Storage,
+    token_address: &str,
+    bidder: &str,
+) -> Result<CollectionOffer, ContractError> {
+    collection_offers()
+        .may_load(storage, (token_address, bidder))?
+        .ok_or(ContractError::OfferDoesNotExist {})
+}
+
+fn get_listing(
+    storage: &dyn Storage,
+    token_id: &str,
+    token_address: &str,
+) -> Result<Listing, ContractError> {
+    listings()
+        .may_load(storage, (token_address, token_id))?
+        .ok_or(ContractError::ListingDoesNotExist {})
+}
+
+fn ensure_asset_allowed(
+    storage: &dyn Storage,
+    token_address: &str,
+    asset: &AssetInfo,
+) -> Result<(), ContractError> {
+    let allowed_assets = match COLLECTIONS
+        .may_load(storage, &Addr::unchecked(token_address))?
+        .and_then(|collection| collection.allowed_assets)
+    {
+        Some(allowed_assets) => allowed_assets,
+        None => CONFIG.load(storage)?.allowed_assets,
     };
-    let token_auction_state =
-        TOKEN_AUCTION_STATE.load(storage, latest_auction_id.u128())?;
+    ensure!(
+        allowed_assets.is_empty() || allowed_assets.contains(asset),
+        ContractError::AssetNotAllowed {
+            asset: asset.to_string(),
+        }
+    );
+    Ok(())
+}
 
-    Ok(token_auction_state)
+fn ensure_collection_allowed(storage: &dyn Storage, collection: &Addr) -> Result<(), ContractError> {
+    let allowed = match COLLECTIONS.may_load(storage, collection)? {
+        Some(info) => info.status == CollectionStatus::Allowed,
+        None => !CONFIG.load(storage)?.restrict_collections,
+    };
+    ensure!(
+        allowed,
+        ContractError::CollectionNotAllowed {
+            collection: collection.to_string(),
+        }
+    );
+    Ok(())
+}
+
+/// Pays the protocol fee and the seller, then sends the NFT to the buyer.
+fn settlement_messages(
+    deps: Deps,
+    token_address: &str,
+    token_id: &str,
+    seller: &str,
+    buyer: &str,
+    amount: Uint128,
+    asset: &AssetInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let item = BundleItem {
+        token_address: token_address.to_string(),
+        token_id: token_id.to_string(),
+    };
+    bundle_settlement_messages(deps, &[item], seller, buyer, amount, asset)
+}
+
+/// Settles the sale of several tokens at once. Fees and royalties are worked
+/// out per token on an equal share of the price.
+fn bundle_settlement_messages(
+    deps: Deps,
+    items: &[BundleItem],
+    seller: &str,
+    buyer: &str,
+    amount: Uint128,
+    asset: &AssetInfo,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let count = items.len() as u128;
+    let share = amount.multiply_ratio(1u128, count);
+
+    let mut fee = Uint128::zero();
+    let mut royalties: Vec<(String, Uint128)> = vec![];
+    for (i, item) in items.iter().enumerate() {
+        // The first token takes the rounding remainder.
+        let item_amount = if i == 0 { amount - share * Uint128::from(count - 1) } else { share };
+        let fee_bps = COLLECTIONS
+            .may_load(deps.storage, &Addr::unchecked(&item.token_address))?
+            .and_then(|collection| collection.fee_bps)
+            .unwrap_or(config.fee_bps);
+        let item_fee = item_amount.multiply_ratio(fee_bps, MAX_FEE_BPS);
+        fee += item_fee;
+        // Pay the creator royalty out of what is left for the seller.
+        if let Some(royalty_info) = royalty_info(deps.querier, &item.token_address, &item.token_id, item_amount) {
+            let royalty = royalty_info.royalty_amount.min(item_amount - item_fee);
+            // An invalid recipient would make every transfer fail, so it is
+            // treated like a collection without royalties.
+            if !royalty.is_zero() && deps.api.addr_validate(&royalty_info.address).is_ok() {
+                royalties.push((royalty_info.address, royalty));
+            }
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !fee.is_zero() {
+        messages.push(asset.transfer_msg(config.fee_collector.as_str(), fee)?);
+    }
+    let mut proceeds = amount - fee;
+    for (recipient, royalty) in royalties {
+        messages.push(asset.transfer_msg(&recipient, royalty)?);
+        proceeds -= royalty;
+    }
+    // Send the rest of the funds to the original owner. Fees and royalties
+    // can take the whole price, and zero transfers are rejected.
+    if !proceeds.is_zero() {
+        messages.push(asset.transfer_msg(seller, proceeds)?);
+    }
+    // Send NFT to the buyer.
+    messages.extend(nft_transfer_messages(items, buyer)?);
+    Ok(messages)
+}
+
+/// Gives `amount` back to `recipient`. The refund is credited to their balance,
+/// so an address refusing funds cannot block the auction, unless they opted
+/// into auto-send.
+fn refund_messages(
+    storage: &mut dyn Storage,
+    recipient: &Addr,
+    asset: &AssetInfo,
+    amount: Uint128,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    if amount.is_zero() {
+        return Ok(vec![]);
+    }
+    if AUTO_SEND.has(storage, recipient) {
+        return Ok(vec![asset.transfer_msg(recipient.as_str(), amount)?]);
+    }
+    let key = asset.key();
+    BALANCES.update(storage, (recipient, &key), |balance| -> StdResult<_> {
+        let mut balance = balance.unwrap_or(Asset {
+            info: asset.clone(),
+            amount: Uint128::zero(),
+        });
+        balance.amount += amount;
+        Ok(balance)
+    })?;
+    Ok(vec![])
+}
+
+/// Settles the highest bidder's committed funds when an auction closes. A
+/// sale keeps what the winner pays and refunds the rest; otherwise the
+/// whole amount is refunded. Other bidders take theirs back with
+/// `RetractBid`, so closing never walks every committed bid.
+fn settle_high_bid(
+    storage: &mut dyn Storage,
+    token_auction_state: &TokenAuctionState,
+    sold: bool,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let key = (
+        token_auction_state.auction_id.u128(),
+        &token_auction_state.high_bidder_addr,
+    );
+    let amount = match COMMITTED_BIDS.may_load(storage, key)? {
+        Some(amount) => amount,
+        None => return Ok(vec![]),
+    };
+    let asset = COMMITTED_ASSETS
+        .may_load(storage, key)?
+        .unwrap_or_else(|| token_auction_state.asset.clone());
+    COMMITTED_BIDS.remove(storage, key);
+    COMMITTED_ASSETS.remove(storage, key);
+    // A winning proxy bid only pays the price it reached.
+    let refund = if sold {
+        amount.saturating_sub(winning_payment(token_auction_state).amount)
+    } else {
+        amount
+    };
+    refund_messages(storage, &token_auction_state.high_bidder_addr, &asset, refund)
+}
+
+/// What the highest bidder pays, in the asset they bid with.
+fn winning_payment(token_auction_state: &TokenAuctionState) -> Asset {
+    token_auction_state.high_bid_asset.clone().unwrap_or(Asset {
+        info: token_auction_state.asset.clone(),
+        amount: token_auction_state.high_bidder_amount,
+    })
+}
+
+/// Value of `payment` in units of `reference`, rounded down.
+fn asset_value(deps: Deps, payment: &Asset, reference: &AssetInfo) -> Result<Uint128, ContractError> {
+    Ok(payment.amount.mul_floor(price_rate(deps, &payment.info, reference)?))
+}
+
+/// Units of `reference` one unit of `asset` is worth, from the configured price source.
+fn price_rate(deps: Deps, asset: &AssetInfo, reference: &AssetInfo) -> Result<Decimal, ContractError> {
+    if asset == reference {
+        return Ok(Decimal::one());
+    }
+    match CONFIG.load(deps.storage)?.price_source {
+        PriceSource::RateTable => RATES
+            .may_load(deps.storage, (&asset.key(), &reference.key()))?
+            .ok_or(ContractError::NoPrice {
+                asset: asset.to_string(),
+            }),
+        PriceSource::Oracle { address } => {
+            let res: PriceResponse = deps.querier.query_wasm_smart(
+                address,
+                &OracleQueryMsg::Price {
+                    asset: asset.clone(),
+                    reference: reference.clone(),
+                },
+            )?;
+            Ok(res.rate)
+        }
+    }
+}
+
+/// Tokens sold by an auction: the bundle if it has one, else its single token.
+fn auction_items(
+    storage: &dyn Storage,
+    token_auction_state: &TokenAuctionState,
+) -> StdResult<Vec<BundleItem>> {
+    Ok(BUNDLES
+        .may_load(storage, token_auction_state.auction_id.u128())?
+        .unwrap_or_else(|| {
+            vec![BundleItem {
+                token_address: token_auction_state.token_address.clone(),
+                token_id: token_auction_state.token_id.clone(),
+            }]
+        }))
+}
+
+fn nft_transfer_messages(items: &[BundleItem], recipient: &str) -> StdResult<Vec<CosmosMsg>> {
+    items
+        .iter()
+        .map(|item| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: item.token_address.clone(),
+                msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                    recipient: recipient.to_string(),
+                    token_id: item.token_id.clone(),
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect()
+}
+
+/// Queries the cw2981 royalty owed on a sale. Returns `None` for collections
+/// that do not implement the extension.
+fn royalty_info(
+    querier: QuerierWrapper,
+    token_address: &str,
+    token_id: &str,
+    sale_price: Uint128,
+) -> Option<RoyaltiesInfoResponse> {
+    querier
+        .query_wasm_smart(
+            token_address,
+            &Cw2981QueryMsg::Extension {
+                msg: Cw2981ExtensionMsg::RoyaltyInfo {
+                    token_id: token_id.to_string(),
+                    sale_price,
+                },
+            },
+        )
+        .ok()
 }
 
 fn owner_of_token(
@@ -391,7 +2932,7 @@ fn owner_of_token(
 ) -> Result<OwnerOfResponse, ContractError> {
     let res: OwnerOfResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: token_addr,
-        msg: to_binary(&Cw721QueryMsg::OwnerOf {
+        msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
             token_id,
             include_expired: None,
         })?,
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +6,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    SemVer(String),
+
     #[error("Invalid expiration")]
     InvalidExpiration {},
 
@@ -36,12 +39,33 @@ pub enum ContractError {
     #[error("InvalidFunds: {msg}")]
     InvalidFunds { msg: String },
 
-    #[error("HighestBidderCannotOutBid")]
-    HighestBidderCannotOutBid {},
+    #[error("HighestBidderCannotRetract")]
+    HighestBidderCannotRetract {},
 
     #[error("BidSmallerThanHighestBid")]
     BidSmallerThanHighestBid {},
 
+    #[error("BidLowerThanMinBid: minimum bid is {min_bid}")]
+    BidLowerThanMinBid { min_bid: Uint128 },
+
+    #[error("BidIncrementTooSmall: bid must be at least {min_required}")]
+    BidIncrementTooSmall { min_required: Uint128 },
+
+    #[error("InvalidReservePrice")]
+    InvalidReservePrice {},
+
+    #[error("InvalidBuyNowPrice")]
+    InvalidBuyNowPrice {},
+
+    #[error("InvalidExtension")]
+    InvalidExtension {},
+
+    #[error("BuyNowNotAvailable")]
+    BuyNowNotAvailable {},
+
+    #[error("BuyNowPriceNotMet: buy now price is {buy_now_price}")]
+    BuyNowPriceNotMet { buy_now_price: Uint128 },
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -50,10 +74,128 @@ pub enum ContractError {
     
     #[error("AuctionRewardAlreadyClaimed")]
     AuctionAlreadyClaimed {},
+
+    #[error("ListingDoesNotExist")]
+    ListingDoesNotExist {},
+
+    #[error("ListingExpired")]
+    ListingExpired {},
+
+    #[error("InvalidPrice")]
+    InvalidPrice {},
+
+    #[error("SellerCannotBuy")]
+    SellerCannotBuy {},
+
+    #[error("InvalidFeeBps")]
+    InvalidFeeBps {},
+
+    #[error("AssetNotAllowed: {asset}")]
+    AssetNotAllowed { asset: String },
+
+    #[error("CollectionNotAllowed: {collection}")]
+    CollectionNotAllowed { collection: String },
+
+    #[error("CollectionDoesNotExist")]
+    CollectionDoesNotExist {},
+
+    #[error("TokenNotReceived")]
+    TokenNotReceived {},
+
+    #[error("OfferDoesNotExist")]
+    OfferDoesNotExist {},
+
+    #[error("OfferAlreadyExists")]
+    OfferAlreadyExists {},
+
+    #[error("OfferExpired")]
+    OfferExpired {},
+
+    #[error("InvalidQuantity")]
+    InvalidQuantity {},
+
+    #[error("InvalidPriceDecay")]
+    InvalidPriceDecay {},
+
+    #[error("PriceNotMet: {price}")]
+    PriceNotMet { price: Uint128 },
+
+    #[error("NotEnglishAuction")]
+    NotEnglishAuction {},
+
+    #[error("AuctionSold")]
+    AuctionSold {},
+
+    #[error("AuctionHasBids")]
+    AuctionHasBids {},
+
+    #[error("NotDutchAuction")]
+    NotDutchAuction {},
+
+    #[error("NoPrice: {asset}")]
+    NoPrice { asset: String },
+
+    #[error("ProxyBidNotSupported")]
+    ProxyBidNotSupported {},
+
+    #[error("NotSealedAuction")]
+    NotSealedAuction {},
+
+    #[error("SealedBidRequired")]
+    SealedBidRequired {},
+
+    #[error("MinBidRequired")]
+    MinBidRequired {},
+
+    #[error("BidAlreadyCommitted")]
+    BidAlreadyCommitted {},
+
+    #[error("NoCommittedBid")]
+    NoCommittedBid {},
+
+    #[error("BidAlreadyRevealed")]
+    BidAlreadyRevealed {},
+
+    #[error("RevealNotStarted")]
+    RevealNotStarted {},
+
+    #[error("InvalidReveal")]
+    InvalidReveal {},
+
+    #[error("BidsSealed")]
+    BidsSealed {},
+
+    #[error("EmptyBundle")]
+    EmptyBundle {},
+
+    #[error("InvalidAllowlist")]
+    InvalidAllowlist {},
+
+    #[error("BidderNotAllowed")]
+    BidderNotAllowed {},
+
+    #[error("InsufficientBalance: {balance}")]
+    InsufficientBalance { balance: Uint128 },
+
+    #[error("InvalidMigrationContract: {contract}")]
+    InvalidMigrationContract { contract: String },
+
+    #[error("MigrationDowngrade: stored {stored}, current {current}")]
+    MigrationDowngrade { stored: String, current: String },
+
+    #[error("MigrationConfigRequired: the stored state has no config, pass an admin")]
+    MigrationConfigRequired {},
 }
 
 impl From<OverflowError> for ContractError {
     fn from(_err: OverflowError) -> Self {
         ContractError::Overflow {}
     }
-}
\ No newline at end of file
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        ContractError::SemVer(err.to_string())
+    }
+}
+
@@ -6,83 +6,381 @@ mod state;
 pub mod mock;
 
 use cosmwasm_std::{
-    Deps, DepsMut, Env, MessageInfo, Response, Uint128, entry_point, to_binary, Binary,
+    Deps, DepsMut, Env, MessageInfo, Response, Uint128, ensure, entry_point, to_json_binary, Binary,
 };
 
+use cw2::set_contract_version;
+use semver::Version;
+
 use {
 	msg::InstantiateMsg,
 	error::ContractError,
-	state::NEXT_AUCTION_ID,
-	msg::{ExecuteMsg, QueryMsg}
+	state::{CONFIG, NEXT_AUCTION_ID, Config, PriceSource},
+	msg::{ExecuteMsg, MigrateMsg, QueryMsg}
 };
 
+const CONTRACT_NAME: &str = "crates.io:cw721-marketplace";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version assumed for deployments instantiated before cw2 info was stored.
+const LEGACY_CONTRACT_VERSION: &str = "0.1.0";
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    ensure!(msg.fee_bps <= contract::MAX_FEE_BPS, ContractError::InvalidFeeBps {});
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    let fee_collector = match msg.fee_collector {
+        Some(fee_collector) => deps.api.addr_validate(&fee_collector)?,
+        None => admin.clone(),
+    };
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin,
+            fee_collector,
+            fee_bps: msg.fee_bps,
+            allowed_assets: msg.allowed_assets,
+            restrict_collections: msg.restrict_collections,
+            default_min_increment: msg.default_min_increment,
+            price_source: PriceSource::default(),
+        },
+    )?;
     NEXT_AUCTION_ID.save(deps.storage, &Uint128::from(1u128))?;
 	Ok(Response::new())
 }
 
 #[entry_point]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
-	use contract::{exec_handle_receive_cw721, exec_place_bid, exec_cancel, exec_claim};
+	use contract::{exec_handle_receive_cw721, exec_handle_receive_cw20, exec_place_bid, exec_place_proxy_bid, exec_retract_bid, exec_commit_bid, exec_reveal_bid, exec_start_bundle_auction, exec_cancel_bundle_draft, exec_relist, exec_update_auction, exec_cancel, exec_claim, exec_buy_now, exec_buy, exec_update_price, exec_delist, exec_make_offer, exec_cancel_offer, exec_make_collection_offer, exec_cancel_collection_offer, exec_update_config, exec_set_rate, exec_withdraw, exec_set_auto_send, exec_add_collection, exec_remove_collection, latest_auction_id};
 	match msg {
 		ExecuteMsg::ReceiveNft(msg) => exec_handle_receive_cw721(deps, env, info, msg),
+		ExecuteMsg::Receive(msg) => exec_handle_receive_cw20(deps, env, info, msg),
         ExecuteMsg::PlaceBid {
             token_id,
             token_address,
-        } => exec_place_bid(deps, env, info, token_id, token_address),
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            exec_place_bid(deps, env, info, auction_id)
+        }
+        ExecuteMsg::PlaceBidById { auction_id } => exec_place_bid(deps, env, info, auction_id),
+        ExecuteMsg::PlaceProxyBid { auction_id } => exec_place_proxy_bid(deps, env, info, auction_id),
+        ExecuteMsg::RetractBid { auction_id } => exec_retract_bid(deps, info, auction_id),
+        ExecuteMsg::CommitBid {
+            auction_id,
+            commitment,
+        } => exec_commit_bid(deps, env, info, auction_id, commitment),
+        ExecuteMsg::RevealBid {
+            auction_id,
+            amount,
+            salt,
+        } => exec_reveal_bid(deps, env, info, auction_id, amount, salt),
+        ExecuteMsg::StartBundleAuction {
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets,
+            allowlist,
+        } => exec_start_bundle_auction(
+            deps,
+            env,
+            info,
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets,
+            allowlist,
+        ),
+        ExecuteMsg::CancelBundleDraft {} => exec_cancel_bundle_draft(deps, info),
+        ExecuteMsg::Relist {
+            auction_id,
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets,
+            allowlist,
+        } => exec_relist(
+            deps,
+            env,
+            info,
+            auction_id,
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+            extension_window,
+            extension_duration,
+            min_increment,
+            accepted_assets,
+            allowlist,
+        ),
+        ExecuteMsg::UpdateAuction {
+            auction_id,
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+        } => exec_update_auction(
+            deps,
+            env,
+            info,
+            auction_id,
+            start_time,
+            duration,
+            asset,
+            min_bid,
+            reserve_price,
+            buy_now_price,
+        ),
         ExecuteMsg::CancelAuction {
             token_id,
             token_address,
-        } => exec_cancel(deps, env, info, token_id, token_address),
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            exec_cancel(deps, env, info, auction_id)
+        }
+        ExecuteMsg::CancelAuctionById { auction_id } => exec_cancel(deps, env, info, auction_id),
         ExecuteMsg::Claim {
             token_id,
             token_address,
-        } => exec_claim(deps, env, info, token_id, token_address),
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            exec_claim(deps, env, info, auction_id)
+        }
+        ExecuteMsg::ClaimById { auction_id } => exec_claim(deps, env, info, auction_id),
+        ExecuteMsg::BuyNow {
+            token_id,
+            token_address,
+        } => {
+            let auction_id = latest_auction_id(deps.storage, &token_id, &token_address)?;
+            exec_buy_now(deps, env, info, auction_id)
+        }
+        ExecuteMsg::BuyNowById { auction_id } => exec_buy_now(deps, env, info, auction_id),
+        ExecuteMsg::Buy {
+            token_id,
+            token_address,
+        } => exec_buy(deps, env, info, token_id, token_address),
+        ExecuteMsg::UpdatePrice {
+            token_id,
+            token_address,
+            price,
+        } => exec_update_price(deps, info, token_id, token_address, price),
+        ExecuteMsg::Delist {
+            token_id,
+            token_address,
+        } => exec_delist(deps, info, token_id, token_address),
+        ExecuteMsg::UpdateConfig {
+            admin,
+            fee_collector,
+            fee_bps,
+            allowed_assets,
+            restrict_collections,
+            default_min_increment,
+            price_source,
+        } => exec_update_config(
+            deps,
+            info,
+            admin,
+            fee_collector,
+            fee_bps,
+            allowed_assets,
+            restrict_collections,
+            default_min_increment,
+            price_source,
+        ),
+        ExecuteMsg::SetRate {
+            asset,
+            reference,
+            rate,
+        } => exec_set_rate(deps, info, asset, reference, rate),
+        ExecuteMsg::MakeOffer {
+            token_address,
+            token_id,
+            expires,
+        } => exec_make_offer(deps, env, info, token_address, token_id, expires),
+        ExecuteMsg::CancelOffer {
+            token_address,
+            token_id,
+        } => exec_cancel_offer(deps, info, token_address, token_id),
+        ExecuteMsg::MakeCollectionOffer {
+            token_address,
+            price,
+            quantity,
+            expires,
+        } => exec_make_collection_offer(deps, env, info, token_address, price, quantity, expires),
+        ExecuteMsg::CancelCollectionOffer { token_address } => {
+            exec_cancel_collection_offer(deps, info, token_address)
+        }
+        ExecuteMsg::Withdraw { asset, amount } => exec_withdraw(deps, info, asset, amount),
+        ExecuteMsg::SetAutoSend { enabled } => exec_set_auto_send(deps, info, enabled),
+        ExecuteMsg::AddCollection {
+            address,
+            status,
+            name,
+            fee_bps,
+            allowed_assets,
+        } => exec_add_collection(deps, info, address, status, name, fee_bps, allowed_assets),
+        ExecuteMsg::RemoveCollection { address } => exec_remove_collection(deps, info, address),
 	}
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: msg::QueryMsg) -> Result<Binary, ContractError> {
-	use contract::{query_auction_infos, query_bids, query_auction_state};
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Deployments from before cw2 was wired in carry no version.
+    let stored = match cw2::CONTRACT.may_load(deps.storage)? {
+        Some(info) => {
+            ensure!(
+                info.contract == CONTRACT_NAME,
+                ContractError::InvalidMigrationContract { contract: info.contract }
+            );
+            info.version
+        }
+        None => LEGACY_CONTRACT_VERSION.to_string(),
+    };
+    let stored_version = Version::parse(&stored)?;
+    ensure!(
+        stored_version <= Version::parse(CONTRACT_VERSION)?,
+        ContractError::MigrationDowngrade {
+            stored,
+            current: CONTRACT_VERSION.to_string(),
+        }
+    );
+
+    let attributes = contract::run_migrations(deps.branch(), &env, &stored_version, &msg)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attributes(attributes))
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: msg::QueryMsg) -> Result<Binary, ContractError> {
+	use contract::{query_config, query_balance, query_rate, query_bidder_allowlist, query_collection, query_collections, query_auction_infos, query_bundle, query_bundle_draft, query_committed_bid, query_bids, query_auction_state, query_current_price, query_listing, query_listings, query_offer, query_offers, query_collection_offer, query_collection_offers};
     match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?).map_err(|err| err.into()),
         QueryMsg::AuctionInfos {
             token_address,
             start_after,
             limit,
-        } => to_binary(&query_auction_infos(deps, token_address, start_after, limit)?).map_err(|err| err.into()),
+        } => to_json_binary(&query_auction_infos(deps, token_address, start_after, limit)?).map_err(|err| err.into()),
+        QueryMsg::Bundle { auction_id } => to_json_binary(&query_bundle(deps, auction_id)?).map_err(|err| err.into()),
+        QueryMsg::BundleDraft { seller } => to_json_binary(&query_bundle_draft(deps, seller)?).map_err(|err| err.into()),
+        QueryMsg::BidderAllowlist { auction_id } => to_json_binary(&query_bidder_allowlist(deps, auction_id)?).map_err(|err| err.into()),
+        QueryMsg::CommittedBid { auction_id, bidder } => to_json_binary(&query_committed_bid(deps, auction_id, bidder)?).map_err(|err| err.into()),
         QueryMsg::Bids {
             auction_id,
             start_after,
             limit,
             order_by,
-        } => to_binary(&query_bids(deps, auction_id, start_after, limit, order_by)?).map_err(|err| err.into()),
+        } => to_json_binary(&query_bids(deps, env, auction_id, start_after, limit, order_by)?).map_err(|err| err.into()),
+        QueryMsg::CurrentPrice { auction_id } => {
+            to_json_binary(&query_current_price(deps, env, auction_id)?).map_err(|err| err.into())
+        }
         QueryMsg::AuctionState {
             auction_id
-        } => to_binary(&query_auction_state(deps, auction_id)?).map_err(|err| err.into())
+        } => to_json_binary(&query_auction_state(deps, auction_id)?).map_err(|err| err.into()),
+        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?).map_err(|err| err.into()),
+        QueryMsg::Rate { asset, reference } => to_json_binary(&query_rate(deps, asset, reference)?).map_err(|err| err.into()),
+        QueryMsg::Collection { address } => to_json_binary(&query_collection(deps, address)?).map_err(|err| err.into()),
+        QueryMsg::Collections {
+            status,
+            start_after,
+            limit,
+        } => to_json_binary(&query_collections(deps, status, start_after, limit)?).map_err(|err| err.into()),
+        QueryMsg::Offer {
+            token_address,
+            token_id,
+            bidder,
+        } => to_json_binary(&query_offer(deps, token_address, token_id, bidder)?).map_err(|err| err.into()),
+        QueryMsg::Offers {
+            token_address,
+            token_id,
+            bidder,
+            start_after,
+            limit,
+        } => to_json_binary(&query_offers(deps, token_address, token_id, bidder, start_after, limit)?).map_err(|err| err.into()),
+        QueryMsg::CollectionOffer {
+            token_address,
+            bidder,
+        } => to_json_binary(&query_collection_offer(deps, token_address, bidder)?).map_err(|err| err.into()),
+        QueryMsg::CollectionOffers {
+            token_address,
+            bidder,
+            start_after,
+            limit,
+        } => to_json_binary(&query_collection_offers(deps, token_address, bidder, start_after, limit)?).map_err(|err| err.into()),
+        QueryMsg::Listing {
+            token_id,
+            token_address,
+        } => to_json_binary(&query_listing(deps, token_id, token_address)?).map_err(|err| err.into()),
+        QueryMsg::Listings {
+            token_address,
+            seller,
+            start_after,
+            limit,
+        } => to_json_binary(&query_listings(deps, token_address, seller, start_after, limit)?).map_err(|err| err.into()),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::{
-        Addr, BankMsg, CosmosMsg, Deps, DepsMut, Response, Timestamp, Uint128, WasmMsg, attr, coins, coin, to_binary, from_binary,
-        testing::{mock_info, mock_env, mock_dependencies},
+        Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, Order, Response, Storage, Timestamp, Uint128, WasmMsg, attr, coins, coin, to_json_binary, from_json,
+        testing::{mock_info, mock_env},
     };
     use crate::{
-        ExecuteMsg, execute, query, msg::Cw721CustomMsg, InstantiateMsg, instantiate, QueryMsg,
-        state::{AuctionInfo, TOKEN_AUCTION_STATE, TokenAuctionState, auction_infos},
+        CONTRACT_NAME, CONTRACT_VERSION, ExecuteMsg, execute, query, migrate, msg::{Cw20HookMsg, Cw721CustomMsg, MigrateMsg}, InstantiateMsg, instantiate, QueryMsg,
+        state::{Asset, AssetInfo, AuctionInfo, AuctionType, Bid, BidderAllowlist, BundleItem, PriceDecay, SealedPricing, BidIncrement, CollectionInfo, CollectionOffer, CollectionStatus, Config, CONFIG, BIDS, COMMITTED_BIDS, LEGACY_CONFIG, LegacyConfig, Listing, NEXT_AUCTION_ID, Offer, PriceSource, TOKEN_AUCTION_STATE, TokenAuctionState, auction_infos, legacy_auction_infos},
         error::ContractError,
-        mock::{custom_mock_dependencies, DUMMY_TOKEN_ADDR, DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN},
+        mock::{custom_mock_dependencies, DUMMY_INVALID_ROYALTY_TOKEN, DUMMY_ORACLE_ADDR, DUMMY_ROYALTY_RECIPIENT, DUMMY_ROYALTY_TOKEN_ADDR, DUMMY_TOKEN_ADDR, DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN},
     };
 
+    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use sha2::{Digest, Sha256};
     use cw721::{Cw721ReceiveMsg, Cw721ExecuteMsg, Expiration};
 
+    fn mock_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            admin: None,
+            fee_collector: Some("fee_collector".to_string()),
+            fee_bps: 0,
+            allowed_assets: vec![],
+            restrict_collections: false,
+            default_min_increment: None,
+        }
+    }
+
     fn check_auction_created(deps: Deps, min_bid: Option<Uint128>) {
         assert_eq!(
             TokenAuctionState {
@@ -90,29 +388,64 @@ mod tests {
                 end_time: Expiration::AtTime(Timestamp::from_seconds(200)),
                 high_bidder_addr: Addr::unchecked(""),
                 high_bidder_amount: Uint128::zero(),
-                coin_denom: "usd".to_string(),
+                asset: AssetInfo::Native { denom: "usd".to_string() },
                 auction_id: 1u128.into(),
                 owner: DUMMY_TOKEN_OWNER.to_string(),
                 token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
                 token_address: DUMMY_TOKEN_ADDR.to_owned(),
                 is_cancelled: false,
+                is_claimed: false,
+                proxy_max: None,
+                auction_type: AuctionType::English,
                 min_bid,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: vec![],
+                high_bid_asset: None,
             },
             TOKEN_AUCTION_STATE.load(deps.storage, 1u128).unwrap()
         );
     }
 
+    fn balance(deps: Deps, address: &str) -> Vec<Asset> {
+        let query_msg = QueryMsg::Balance {
+            address: address.to_owned(),
+        };
+        from_json(query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    }
+
+    fn retract_bid(deps: DepsMut, bidder: &str) -> Vec<CosmosMsg> {
+        let msg = ExecuteMsg::RetractBid {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps, mock_env(), mock_info(bidder, &[]), msg).unwrap();
+        res.messages.into_iter().map(|msg| msg.msg).collect()
+    }
+
     fn start_auction(deps: DepsMut, min_bid: Option<Uint128>) {
         let custom_msg = Cw721CustomMsg::StartAuction {
             start_time: 100000,
             duration: 100000,
-            coin_denom: "usd".to_string(),
+            asset: AssetInfo::Native { denom: "usd".to_string() },
             min_bid,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
         };
+        start_auction_with(deps, custom_msg);
+    }
+
+    fn start_auction_with(deps: DepsMut, custom_msg: Cw721CustomMsg) {
         let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
             sender: DUMMY_TOKEN_OWNER.to_owned(),
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&custom_msg).unwrap(),
+            msg: to_json_binary(&custom_msg).unwrap(),
         });
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(0u64);
@@ -121,23 +454,387 @@ mod tests {
         let _res = execute(deps, env, info, msg).unwrap();
     }
 
+    #[test]
+    fn test_instantiate() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+
+        let mut msg = mock_instantiate_msg();
+        msg.fee_bps = 10_001;
+        let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(ContractError::InvalidFeeBps {}, res.unwrap_err());
+
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+        let res: Config = from_json(query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(
+            Config {
+                admin: Addr::unchecked("owner"),
+                fee_collector: Addr::unchecked("fee_collector"),
+                fee_bps: 0,
+                allowed_assets: vec![],
+                restrict_collections: false,
+                default_min_increment: None,
+                price_source: PriceSource::RateTable,
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_update_config() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig {
+            admin: Some("new_admin".to_string()),
+            fee_collector: None,
+            fee_bps: Some(250),
+            allowed_assets: Some(vec![AssetInfo::Native { denom: "usd".to_string() }]),
+            restrict_collections: None,
+            default_min_increment: None,
+            price_source: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        let res: Config = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(
+            Config {
+                admin: Addr::unchecked("new_admin"),
+                fee_collector: Addr::unchecked("fee_collector"),
+                fee_bps: 250,
+                allowed_assets: vec![AssetInfo::Native { denom: "usd".to_string() }],
+                restrict_collections: false,
+                default_min_increment: None,
+                price_source: PriceSource::RateTable,
+            },
+            res
+        );
+
+        let msg = ExecuteMsg::UpdateConfig {
+            admin: None,
+            fee_collector: None,
+            fee_bps: Some(10_001),
+            allowed_assets: None,
+            restrict_collections: None,
+            default_min_increment: None,
+            price_source: None,
+        };
+        let res = execute(deps.as_mut(), env, mock_info("new_admin", &[]), msg);
+        assert_eq!(ContractError::InvalidFeeBps {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_start_auction_asset_not_allowed() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.allowed_assets = vec![AssetInfo::Native { denom: "uluna".to_string() }];
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(
+            ContractError::AssetNotAllowed {
+                asset: "usd".to_string()
+            },
+            res.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_exec_start_auction_token_not_received() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
+        // ReceiveNft called directly, without the token being sent.
+        deps.querier.set_token_owner(DUMMY_UNCLAIMED_TOKEN, DUMMY_TOKEN_OWNER);
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::TokenNotReceived {}, res.unwrap_err());
+        assert!(TOKEN_AUCTION_STATE.may_load(&deps.storage, 1u128).unwrap().is_none());
+    }
+
+    fn add_collection(deps: DepsMut, address: &str, status: CollectionStatus, fee_bps: Option<u64>) {
+        let msg = ExecuteMsg::AddCollection {
+            address: address.to_string(),
+            status,
+            name: None,
+            fee_bps,
+            allowed_assets: None,
+        };
+        let _res = execute(deps, mock_env(), mock_info("owner", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn test_exec_receive_nft_collection_not_allowed() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
+
+        let hook_msg = Cw721CustomMsg::ListForSale {
+            price: Uint128::from(100u128),
+            denom: "usd".to_string(),
+            expires: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+
+        // Denied collections are rejected even when collections are unrestricted.
+        add_collection(deps.as_mut(), DUMMY_TOKEN_ADDR, CollectionStatus::Denied, None);
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+        assert_eq!(
+            ContractError::CollectionNotAllowed {
+                collection: DUMMY_TOKEN_ADDR.to_string()
+            },
+            res.unwrap_err()
+        );
+
+        let update_msg = ExecuteMsg::UpdateConfig {
+            admin: None,
+            fee_collector: None,
+            fee_bps: None,
+            allowed_assets: None,
+            restrict_collections: Some(true),
+            default_min_increment: None,
+            price_source: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), update_msg).unwrap();
+
+        let info = mock_info(DUMMY_ROYALTY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
+        assert_eq!(
+            ContractError::CollectionNotAllowed {
+                collection: DUMMY_ROYALTY_TOKEN_ADDR.to_string()
+            },
+            res.unwrap_err()
+        );
+
+        add_collection(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, CollectionStatus::Allowed, None);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_exec_add_remove_collection() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let msg = ExecuteMsg::AddCollection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+            status: CollectionStatus::Allowed,
+            name: Some("Dummies".to_string()),
+            fee_bps: Some(10_001),
+            allowed_assets: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+        assert_eq!(ContractError::InvalidFeeBps {}, res.unwrap_err());
+
+        let msg = ExecuteMsg::AddCollection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+            status: CollectionStatus::Allowed,
+            name: Some("Dummies".to_string()),
+            fee_bps: Some(100),
+            allowed_assets: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+        add_collection(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, CollectionStatus::Denied, None);
+
+        let query_msg = QueryMsg::Collection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res: CollectionInfo = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        let dummies = CollectionInfo {
+            address: Addr::unchecked(DUMMY_TOKEN_ADDR),
+            status: CollectionStatus::Allowed,
+            name: Some("Dummies".to_string()),
+            fee_bps: Some(100),
+            allowed_assets: None,
+        };
+        assert_eq!(dummies, res);
+
+        let query_msg = QueryMsg::Collections {
+            status: Some(CollectionStatus::Denied),
+            start_after: None,
+            limit: None,
+        };
+        let res: Vec<CollectionInfo> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(vec![Addr::unchecked(DUMMY_ROYALTY_TOKEN_ADDR)], res.into_iter().map(|c| c.address).collect::<Vec<Addr>>());
+
+        let query_msg = QueryMsg::Collections {
+            status: None,
+            start_after: Some(DUMMY_ROYALTY_TOKEN_ADDR.to_string()),
+            limit: None,
+        };
+        let res: Vec<CollectionInfo> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(vec![dummies], res);
+
+        let msg = ExecuteMsg::RemoveCollection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+        assert_eq!(ContractError::CollectionDoesNotExist {}, res.unwrap_err());
+
+        let query_msg = QueryMsg::Collection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res = query(deps.as_ref(), env, query_msg);
+        assert_eq!(ContractError::CollectionDoesNotExist {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_collection_overrides() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.fee_bps = 500;
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::AddCollection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+            status: CollectionStatus::Allowed,
+            name: None,
+            fee_bps: Some(1000),
+            allowed_assets: Some(vec![AssetInfo::Native { denom: "uluna".to_string() }]),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::ListForSale {
+            price: Uint128::from(100u128),
+            denom: "usd".to_string(),
+            expires: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_ADDR, &[]), msg);
+        assert_eq!(
+            ContractError::AssetNotAllowed {
+                asset: "usd".to_string()
+            },
+            res.unwrap_err()
+        );
+
+        let hook_msg = Cw721CustomMsg::ListForSale {
+            price: Uint128::from(100u128),
+            denom: "uluna".to_string(),
+            expires: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_ADDR, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("buyer", &coins(100, "uluna")), msg).unwrap();
+        // The collection's 10% fee replaces the marketplace's 5%.
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "fee_collector".to_owned(),
+                    amount: coins(10, "uluna"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(90, "uluna"),
+                }),
+            ],
+            res.messages[..2].iter().map(|msg| msg.msg.clone()).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
     #[test]
     fn test_exec_start_auction() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let env = mock_env();
         let info = mock_info("owner", &[]);
-        let _res = instantiate(deps.as_mut(), env, info, InstantiateMsg {}).unwrap();
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
 
         let custom_msg = Cw721CustomMsg::StartAuction {
             start_time: 100000,
             duration: 100000,
-            coin_denom: "usd".to_string(),
+            asset: AssetInfo::Native { denom: "usd".to_string() },
             min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
         };
         let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
             sender: DUMMY_TOKEN_OWNER.to_owned(),
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&custom_msg).unwrap(),
+            msg: to_json_binary(&custom_msg).unwrap(),
         });
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(0u64);
@@ -160,10 +857,10 @@ mod tests {
 
     #[test]
     fn test_exec_place_bid() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
@@ -198,7 +895,7 @@ mod tests {
             auction_infos()
                 .load(
                     &deps.storage,
-                    &(DUMMY_UNCLAIMED_TOKEN.to_owned() + DUMMY_TOKEN_ADDR)
+                    (&Addr::unchecked(DUMMY_TOKEN_ADDR), DUMMY_UNCLAIMED_TOKEN)
                 )
                 .unwrap()
         );
@@ -206,10 +903,10 @@ mod tests {
 
     #[test]
     fn test_exec_place_bid_non_existing_auction() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let env = mock_env();
         let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         let msg = ExecuteMsg::PlaceBid {
@@ -223,10 +920,10 @@ mod tests {
 
     #[test]
     fn test_exec_place_bid_auction_not_started() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
@@ -246,10 +943,10 @@ mod tests {
 
     #[test]
     fn test_exec_place_bid_ended_auction() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
@@ -269,10 +966,10 @@ mod tests {
 
     #[test]
     fn test_exec_place_bid_owner_cannot_bid() {
-        let mut deps = mock_dependencies();
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
@@ -290,12 +987,20 @@ mod tests {
         assert_eq!(ContractError::TokenOwnerCannotBid {}, res.unwrap_err());
     }
 
+    fn committed_bid(deps: Deps, bidder: &str) -> Uint128 {
+        let query_msg = QueryMsg::CommittedBid {
+            auction_id: Uint128::from(1u128),
+            bidder: bidder.to_owned(),
+        };
+        from_json(query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    }
+
     #[test]
-    fn test_exec_place_bid_highest_bidder_cannot_bid() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_bid_top_up() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
@@ -310,226 +1015,546 @@ mod tests {
         let info = mock_info("sender", &coins(100, "usd".to_string()));
         let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
 
-        env.block.time = Timestamp::from_seconds(160);
-        let info = mock_info("sender", &coins(200, "usd".to_string()));
-        let res = execute(deps.as_mut(), env, info, msg);
+        // The highest bidder can raise their own bid.
+        let info = mock_info("sender", &coins(50, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(Uint128::from(150u128), committed_bid(deps.as_ref(), "sender"));
+
+        // Outbid funds stay committed and only the difference is sent.
+        let info = mock_info("other", &coins(200, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert!(res.messages.is_empty());
+        let info = mock_info("sender", &coins(50, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(ContractError::BidSmallerThanHighestBid {}, res.unwrap_err());
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Addr::unchecked("sender"), state.high_bidder_addr);
+        assert_eq!(Uint128::from(250u128), state.high_bidder_amount);
+
+        // Only bidders no longer winning can take their funds back.
+        let retract_msg = ExecuteMsg::RetractBid {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("sender", &[]), retract_msg.clone());
+        assert_eq!(ContractError::HighestBidderCannotRetract {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), retract_msg.clone()).unwrap();
         assert_eq!(
-            ContractError::HighestBidderCannotOutBid {},
-            res.unwrap_err()
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "other".to_string(),
+                amount: coins(200, "usd"),
+            })],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
         );
+        let res = execute(deps.as_mut(), env, mock_info("other", &[]), retract_msg);
+        assert_eq!(ContractError::NoCommittedBid {}, res.unwrap_err());
     }
 
     #[test]
-    fn test_exec_place_bid_smaller_than_highest_bid() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_proxy_bid() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-        start_auction(deps.as_mut(), None);
-        check_auction_created(deps.as_ref(), None);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: Some(Uint128::from(100u128)),
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: Some(BidIncrement {
+                    amount: Uint128::from(10u128),
+                    bps: 0,
+                }),
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
 
-        let msg = ExecuteMsg::PlaceBid {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        env.block.time = Timestamp::from_seconds(150);
+        let auction_id = Uint128::from(1u128);
+        let proxy_msg = ExecuteMsg::PlaceProxyBid { auction_id };
+        let bid_msg = ExecuteMsg::PlaceBidById { auction_id };
+        let bid = |deps: DepsMut, bidder: &str, amount: u128, msg: &ExecuteMsg| {
+            execute(deps, env.clone(), mock_info(bidder, &coins(amount, "usd")), msg.clone()).unwrap()
         };
 
-        env.block.time = Timestamp::from_seconds(150);
-        let info = mock_info("sender", &coins(100, "usd".to_string()));
-        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        // A proxy bid opens at the minimum bid and hides its maximum.
+        let _res = bid(deps.as_mut(), "proxy_a", 500, &proxy_msg);
+        let query_msg = QueryMsg::AuctionState { auction_id };
+        let state: TokenAuctionState = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(Uint128::from(100u128), state.high_bidder_amount);
+        assert_eq!(None, state.proxy_max);
 
-        env.block.time = Timestamp::from_seconds(160);
-        let info = mock_info("other", &coins(50, "usd".to_string()));
-        let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(ContractError::BidSmallerThanHighestBid {}, res.unwrap_err());
-    }
+        // Manual and proxy bids below the maximum are answered one increment up.
+        let res = bid(deps.as_mut(), "manual_b", 200, &bid_msg);
+        assert_eq!(
+            vec![
+                attr("action", "bid"),
+                attr("token_id", DUMMY_UNCLAIMED_TOKEN),
+                attr("bider", "manual_b"),
+                attr("amount", "200"),
+                attr("high_bidder", "proxy_a"),
+                attr("high_bid", "210"),
+            ],
+            res.attributes
+        );
+        let _res = bid(deps.as_mut(), "proxy_c", 400, &proxy_msg);
+        // Matching the maximum is not enough: the earlier bid wins ties.
+        let _res = bid(deps.as_mut(), "manual_b", 300, &bid_msg);
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Addr::unchecked("proxy_a"), state.high_bidder_addr);
+        assert_eq!(Uint128::from(500u128), state.high_bidder_amount);
+
+        // A higher maximum takes the lead one increment above the old one.
+        let _res = bid(deps.as_mut(), "proxy_d", 1000, &proxy_msg);
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Addr::unchecked("proxy_d"), state.high_bidder_addr);
+        assert_eq!(Uint128::from(510u128), state.high_bidder_amount);
+        assert_eq!(Some(Uint128::from(1000u128)), state.proxy_max);
+        // The escrowed maximum is not visible through the committed bid.
+        assert_eq!(Uint128::from(510u128), committed_bid(deps.as_ref(), "proxy_d"));
+        assert_eq!(Uint128::from(500u128), committed_bid(deps.as_ref(), "proxy_a"));
+
+        let query_msg = QueryMsg::Bids {
+            auction_id,
+            start_after: None,
+            limit: None,
+            order_by: None,
+        };
+        let bids: Vec<Bid> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(
+            vec![
+                ("proxy_a", 100u128),
+                ("manual_b", 200),
+                ("proxy_a", 210),
+                ("proxy_c", 400),
+                ("proxy_a", 410),
+                ("manual_b", 500),
+                ("proxy_a", 500),
+                ("proxy_d", 510),
+            ],
+            bids.iter()
+                .map(|bid| (bid.bidder.as_str(), bid.amount.u128()))
+                .collect::<Vec<(&str, u128)>>()
+        );
+
+        // The winner pays the price reached and gets the rest of the maximum back.
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById { auction_id };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(510, "usd"),
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(Uint128::from(490u128), balance(deps.as_ref(), "proxy_d")[0].amount);
+        // Losing bidders take their funds back once the auction closes.
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "proxy_a".to_string(),
+                amount: coins(500, "usd"),
+            })],
+            retract_bid(deps.as_mut(), "proxy_a")
+        );
+    }
+
+    fn start_multi_asset_auction(deps: DepsMut) {
+        start_auction_with(
+            deps,
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: Some(vec![AssetInfo::Native { denom: "eur".to_string() }]),
+                allowlist: None,
+            },
+        );
+    }
 
     #[test]
-    fn test_exec_place_bid_invalid_coins() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_proxy_bid_reserve() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: Some(Uint128::from(500u128)),
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
 
-        start_auction(deps.as_mut(), None);
-        check_auction_created(deps.as_ref(), None);
+        env.block.time = Timestamp::from_seconds(150);
+        let auction_id = Uint128::from(1u128);
+        let proxy_msg = ExecuteMsg::PlaceProxyBid { auction_id };
+        let high_bid = |deps: Deps| TOKEN_AUCTION_STATE.load(deps.storage, 1u128).unwrap().high_bidder_amount;
+
+        // A maximum below the reserve keeps bidding from the bottom.
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("proxy_a", &coins(300, "usd")), proxy_msg.clone()).unwrap();
+        assert_eq!(Uint128::from(1u128), high_bid(deps.as_ref()));
+        // Raising it past the reserve bids the reserve.
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("proxy_a", &coins(300, "usd")), proxy_msg.clone()).unwrap();
+        assert_eq!(Uint128::from(500u128), high_bid(deps.as_ref()));
+        // A new leader whose maximum covers the reserve bids at least the reserve.
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("proxy_b", &coins(1000, "usd")), proxy_msg).unwrap();
+        assert_eq!(Uint128::from(601u128), high_bid(deps.as_ref()));
+
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById { auction_id };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert!(!res.attributes.contains(&attr("reserve_met", "false")));
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(601, "usd"),
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(Uint128::from(399u128), balance(deps.as_ref(), "proxy_b")[0].amount);
+    }
+
+    #[test]
+    fn test_exec_place_proxy_bid_lone_reserve() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: Some(Uint128::from(500u128)),
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
 
+        // A single proxy bid covering the reserve sells at the reserve.
         env.block.time = Timestamp::from_seconds(150);
+        let auction_id = Uint128::from(1u128);
+        let msg = ExecuteMsg::PlaceProxyBid { auction_id };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("proxy_a", &coins(1000, "usd")), msg).unwrap();
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById { auction_id };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(500, "usd"),
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(Uint128::from(500u128), balance(deps.as_ref(), "proxy_a")[0].amount);
+    }
 
-        let error = ContractError::InvalidFunds {
-            msg: "Auctions require exactly one coin to be sent.".to_string(),
-        };
-        let msg = ExecuteMsg::PlaceBid {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_string(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
-        };
+    #[test]
+    fn test_exec_place_bid_accepted_asset() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        // No coins
-        let info = mock_info("sender", &[]);
-        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
-        assert_eq!(error, res.unwrap_err());
+        start_multi_asset_auction(deps.as_mut());
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        env.block.time = Timestamp::from_seconds(150);
 
-        // Multiple coins
-        let info = mock_info("sender", &[coin(100, "usd"), coin(100, "uluna")]);
-        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
-        assert_eq!(error, res.unwrap_err());
+        // Bids in other accepted assets need a rate to be valued.
+        let res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "eur")), msg.clone());
+        assert_eq!(
+            ContractError::NoPrice {
+                asset: "eur".to_string()
+            },
+            res.unwrap_err()
+        );
 
-        let error = ContractError::InvalidFunds {
-            msg: "No usd assets are provided to auction".to_string(),
+        let rate_msg = ExecuteMsg::SetRate {
+            asset: AssetInfo::Native { denom: "eur".to_string() },
+            reference: AssetInfo::Native { denom: "usd".to_string() },
+            rate: Some(Decimal::percent(150)),
         };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), rate_msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), rate_msg).unwrap();
 
-        // Invalid denom
-        let info = mock_info("sender", &[coin(100, "uluna")]);
-        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
-        assert_eq!(error, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "eur")), msg.clone()).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Uint128::from(150u128), state.high_bidder_amount);
+        assert_eq!(
+            Some(Asset {
+                info: AssetInfo::Native { denom: "eur".to_string() },
+                amount: Uint128::from(100u128),
+            }),
+            state.high_bid_asset
+        );
 
-        // Correct denom
-        let info = mock_info("sender", &[coin(0, "usd")]);
-        let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(error, res.unwrap_err());
+        // Bids are compared by value in the auction's asset.
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &coins(140, "usd")), msg.clone());
+        assert_eq!(ContractError::BidSmallerThanHighestBid {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("other", &coins(160, "usd")), msg.clone()).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(None, state.high_bid_asset);
+
+        // Committed funds can only be topped up in the same asset.
+        let res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(20, "usd")), msg.clone());
+        assert!(matches!(res.unwrap_err(), ContractError::InvalidFunds { .. }));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(20, "eur")), msg).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Addr::unchecked("sender"), state.high_bidder_addr);
+        assert_eq!(Uint128::from(180u128), state.high_bidder_amount);
+
+        // The winner pays in the asset they bid with.
+        env.block.time = Timestamp::from_seconds(250);
+        let claim_msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("any_user", &[]), claim_msg).unwrap();
+        let transfer_nft_msg = Cw721ExecuteMsg::TransferNft {
+            recipient: "sender".to_string(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+        };
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(120, "eur"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_string(),
+                    msg: to_json_binary(&transfer_nft_msg).unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "other".to_string(),
+                amount: coins(160, "usd"),
+            })],
+            retract_bid(deps.as_mut(), "other")
+        );
     }
+
     #[test]
-    fn test_exec_start_auction_start_time_in_past() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
+    fn test_price_oracle() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let update_msg = ExecuteMsg::UpdateConfig {
+            admin: None,
+            fee_collector: None,
+            fee_bps: None,
+            allowed_assets: None,
+            restrict_collections: None,
+            default_min_increment: None,
+            price_source: Some(PriceSource::Oracle {
+                address: Addr::unchecked(DUMMY_ORACLE_ADDR),
+            }),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), update_msg).unwrap();
 
-        let hook_msg = Cw721CustomMsg::StartAuction {
-            start_time: 100000,
-            duration: 100000,
-            coin_denom: "usd".to_string(),
-            min_bid: None,
+        let query_msg = QueryMsg::Rate {
+            asset: AssetInfo::Native { denom: "eur".to_string() },
+            reference: AssetInfo::Native { denom: "usd".to_string() },
         };
-        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
-            sender: DUMMY_TOKEN_OWNER.to_owned(),
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&hook_msg).unwrap(),
-        });
-        let mut env = mock_env();
+        let rate: Decimal = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(Decimal::percent(200), rate);
+
+        start_multi_asset_auction(deps.as_mut());
         env.block.time = Timestamp::from_seconds(150);
 
-        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
-        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        // Proxy bids stay in the auction's asset.
+        let proxy_msg = ExecuteMsg::PlaceProxyBid {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "eur")), proxy_msg);
+        assert!(matches!(res.unwrap_err(), ContractError::InvalidFunds { .. }));
 
-        assert_eq!(
-            ContractError::InvalidStartTime {
-                current_time: env.block.time.nanos() / 1000000,
-                current_block: env.block.height,
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let _res = execute(deps.as_mut(), env, mock_info("sender", &coins(60, "eur")), msg).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Uint128::from(120u128), state.high_bidder_amount);
+    }
+
+    fn start_private_auction(deps: DepsMut, allowlist: BidderAllowlist) {
+        start_auction_with(
+            deps,
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: Some(allowlist),
             },
-            res.unwrap_err()
         );
     }
 
     #[test]
-    fn test_exec_start_auction_zero_start_time() {
-        let mut deps = mock_dependencies();
-        let env = mock_env();
+    fn test_exec_place_bid_allowlist() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        let hook_msg = Cw721CustomMsg::StartAuction {
-            start_time: 0,
-            duration: 1,
-            coin_denom: "usd".to_string(),
-            min_bid: None,
+        start_private_auction(
+            deps.as_mut(),
+            BidderAllowlist::Addresses {
+                addresses: vec![Addr::unchecked("sender")],
+            },
+        );
+        let query_msg = QueryMsg::BidderAllowlist {
+            auction_id: Uint128::from(1u128),
         };
-        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
-            sender: DUMMY_TOKEN_OWNER.to_owned(),
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&hook_msg).unwrap(),
-        });
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(0);
+        let res: Option<BidderAllowlist> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(
+            Some(BidderAllowlist::Addresses {
+                addresses: vec![Addr::unchecked("sender")],
+            }),
+            res
+        );
 
-        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
-        let res = execute(deps.as_mut(), env, info, msg);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        env.block.time = Timestamp::from_seconds(150);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &coins(100, "usd")), msg.clone());
+        assert_eq!(ContractError::BidderNotAllowed {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "usd")), msg).unwrap();
 
-        assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+        // Token-gated: only holders of the collection can bid.
+        let cancel_msg = ExecuteMsg::CancelAuctionById {
+            auction_id: Uint128::from(1u128),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_OWNER, &[]), cancel_msg).unwrap();
+        start_private_auction(
+            deps.as_mut(),
+            BidderAllowlist::Holders {
+                collection: Addr::unchecked(DUMMY_TOKEN_ADDR),
+            },
+        );
+        deps.querier.set_token_owner("holder_token", "holder");
+
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(2u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "usd")), msg.clone());
+        assert_eq!(ContractError::BidderNotAllowed {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env, mock_info("holder", &coins(100, "usd")), msg).unwrap();
     }
 
     #[test]
-    fn test_exec_start_auction_zero_duration() {
-        let mut deps = mock_dependencies();
+    fn test_exec_start_auction_empty_allowlist() {
+        let mut deps = custom_mock_dependencies(&[]);
         let env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        let hook_msg = Cw721CustomMsg::StartAuction {
-            start_time: 100,
-            duration: 0,
-            coin_denom: "usd".to_string(),
+        let custom_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
             min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: Some(BidderAllowlist::Addresses { addresses: vec![] }),
         };
         let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
             sender: DUMMY_TOKEN_OWNER.to_owned(),
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&hook_msg).unwrap(),
+            msg: to_json_binary(&custom_msg).unwrap(),
         });
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(0);
-
-        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
-        let res = execute(deps.as_mut(), env, info, msg);
-
-        assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), env, mock_info(DUMMY_TOKEN_ADDR, &[]), msg);
+        assert_eq!(ContractError::InvalidAllowlist {}, res.unwrap_err());
     }
 
     #[test]
-    fn test_exec_cancel_no_bids() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_bid_smaller_than_highest_bid() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         start_auction(deps.as_mut(), None);
+        check_auction_created(deps.as_ref(), None);
 
-        let msg = ExecuteMsg::CancelAuction {
+        let msg = ExecuteMsg::PlaceBid {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
             token_address: DUMMY_TOKEN_ADDR.to_string(),
         };
 
         env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
 
-        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-        assert_eq!(
-            Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
-                msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-                    recipient: DUMMY_TOKEN_OWNER.to_owned(),
-                    token_id: DUMMY_UNCLAIMED_TOKEN.to_owned()
-                })
-                .unwrap(),
-                funds: vec![],
-            })),
-            res
-        );
-
-        assert!(
-            TOKEN_AUCTION_STATE
-                .load(deps.as_ref().storage, 1u128)
-                .unwrap()
-                .is_cancelled
-        );
+        env.block.time = Timestamp::from_seconds(160);
+        let info = mock_info("other", &coins(50, "usd".to_string()));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::BidSmallerThanHighestBid {}, res.unwrap_err());
     }
 
     #[test]
-    fn test_exec_cancel_with_bids() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_bid_lower_than_min_bid() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
+        let msg = mock_instantiate_msg();
         let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        start_auction(deps.as_mut(), None);
+        start_auction(deps.as_mut(), Some(Uint128::from(1000u128)));
+        check_auction_created(deps.as_ref(), Some(Uint128::from(1000u128)));
 
         let msg = ExecuteMsg::PlaceBid {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
@@ -537,267 +1562,3136 @@ mod tests {
         };
 
         env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(999, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(
+            ContractError::BidLowerThanMinBid {
+                min_bid: Uint128::from(1000u128)
+            },
+            res.unwrap_err()
+        );
 
-        let info = mock_info("bidder", &coins(100, "usd"));
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("sender", &coins(1000, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
 
-        let msg = ExecuteMsg::CancelAuction {
+    fn start_buy_now_auction(deps: DepsMut, buy_now_price: Option<Uint128>) {
+        start_auction_with(
+            deps,
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
+    }
+
+    #[test]
+    fn test_exec_place_bid_buy_now() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_buy_now_auction(deps.as_mut(), Some(Uint128::from(500u128)));
+
+        let msg = ExecuteMsg::PlaceBid {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
             token_address: DUMMY_TOKEN_ADDR.to_string(),
         };
 
-        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
 
+        env.block.time = Timestamp::from_seconds(160);
+        let info = mock_info("other", &coins(600, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
         assert_eq!(
             Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(500, "usd"),
+                }))
                 .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
-                    msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
-                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned()
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "other".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
                     })
                     .unwrap(),
                     funds: vec![],
                 }))
                 .add_message(CosmosMsg::Bank(BankMsg::Send {
-                    to_address: "bidder".to_string(),
-                    amount: coins(100, "usd")
-                })),
+                    to_address: "other".to_string(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_attributes(vec![
+                    attr("action", "buy_now"),
+                    attr("token_id", DUMMY_UNCLAIMED_TOKEN),
+                    attr("bider", "other"),
+                    attr("amount", "500"),
+                ]),
             res
         );
 
-        assert!(
-            TOKEN_AUCTION_STATE
-                .load(deps.as_ref().storage, 1u128)
-                .unwrap()
-                .is_cancelled
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "sender".to_string(),
+                amount: coins(100, "usd"),
+            })],
+            retract_bid(deps.as_mut(), "sender")
         );
+
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Expiration::AtTime(Timestamp::from_seconds(160)), state.end_time);
+        assert_eq!(Uint128::from(500u128), state.high_bidder_amount);
+
+        // The auction is over once bought.
+        let info = mock_info("late", &coins(700, "usd".to_string()));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
     }
 
     #[test]
-    fn test_exec_cancel_not_token_owner() {
-        let mut deps = mock_dependencies();
+    fn test_exec_place_bid_extends_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-        start_auction(deps.as_mut(), None);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: Some(10000),
+                extension_duration: Some(30000),
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
 
-        let msg = ExecuteMsg::CancelAuction {
+        let msg = ExecuteMsg::PlaceBid {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
             token_address: DUMMY_TOKEN_ADDR.to_string(),
         };
 
+        // Outside the window the end time is unchanged.
         env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert_eq!(4, res.attributes.len());
 
-        let info = mock_info("anyone", &[]);
-        let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+        env.block.time = Timestamp::from_seconds(195);
+        let info = mock_info("other", &coins(200, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert_eq!(
+            attr("end_time", "expiration time: 225.000000000"),
+            res.attributes[4]
+        );
+        assert_eq!(
+            Expiration::AtTime(Timestamp::from_seconds(225)),
+            TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap().end_time
+        );
+
+        // The auction is still open past its original end time.
+        env.block.time = Timestamp::from_seconds(210);
+        let info = mock_info("sender", &coins(300, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
     }
 
     #[test]
-    fn test_exec_cancel_ended_auction() {
-        let mut deps = mock_dependencies();
-        let mut env = mock_env();
+    fn test_exec_start_auction_invalid_extension() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-        start_auction(deps.as_mut(), None);
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
 
-        let msg = ExecuteMsg::CancelAuction {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: Some(10000),
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
         };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
 
-        env.block.time = Timestamp::from_seconds(300);
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(ContractError::InvalidExtension {}, res.unwrap_err());
 
-        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        // Windows that overflow once converted to nanoseconds are rejected.
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: Some(u64::MAX),
+            extension_duration: Some(10000),
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
         let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
+        assert_eq!(ContractError::InvalidExtension {}, res.unwrap_err());
     }
 
     #[test]
-    fn test_exec_claim_no_bids() {
+    fn test_exec_buy_now() {
         let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-        start_auction(deps.as_mut(), None);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        env.block.time = Timestamp::from_seconds(250);
+        start_buy_now_auction(deps.as_mut(), Some(Uint128::from(500u128)));
 
-        let msg = ExecuteMsg::Claim {
+        let msg = ExecuteMsg::BuyNow {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
             token_address: DUMMY_TOKEN_ADDR.to_string(),
         };
 
-        let info = mock_info("any_user", &[]);
+        env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(400, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(
+            ContractError::BuyNowPriceNotMet {
+                buy_now_price: Uint128::from(500u128)
+            },
+            res.unwrap_err()
+        );
+
+        let info = mock_info("sender", &coins(500, "usd".to_string()));
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
         assert_eq!(
             Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(500, "usd"),
+                }))
                 .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
                     contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
-                    msg: to_binary(&Cw721ExecuteMsg::TransferNft {
-                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "sender".to_string(),
                         token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
                     })
                     .unwrap(),
                     funds: vec![],
                 }))
-                .add_attribute("action", "claim")
-                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
-                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
-                .add_attribute("recipient", DUMMY_TOKEN_OWNER)
-                .add_attribute("winning_bid_amount", Uint128::zero())
-                .add_attribute("auction_id", "1"),
+                .add_attributes(vec![
+                    attr("action", "buy_now"),
+                    attr("token_id", DUMMY_UNCLAIMED_TOKEN),
+                    attr("bider", "sender"),
+                    attr("amount", "500"),
+                ]),
             res
         );
     }
 
     #[test]
-    fn test_exec_claim() {
+    fn test_exec_buy_now_not_available() {
         let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
         start_auction(deps.as_mut(), None);
 
-        let msg = ExecuteMsg::PlaceBid {
+        let msg = ExecuteMsg::BuyNow {
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
             token_address: DUMMY_TOKEN_ADDR.to_string(),
         };
 
         env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(500, "usd".to_string()));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::BuyNowNotAvailable {}, res.unwrap_err());
+    }
 
-        let info = mock_info("sender", &coins(100, "usd".to_string()));
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    fn start_dutch_auction(deps: DepsMut, decay: PriceDecay) {
+        start_auction_with(
+            deps,
+            Cw721CustomMsg::StartDutchAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                start_price: Uint128::from(1000u128),
+                end_price: Uint128::from(200u128),
+                decay,
+            },
+        );
+    }
 
-        env.block.time = Timestamp::from_seconds(250);
+    fn current_price(deps: Deps, env: Env) -> Uint128 {
+        let query_msg = QueryMsg::CurrentPrice {
+            auction_id: Uint128::from(1u128),
+        };
+        from_json(query(deps, env, query_msg).unwrap()).unwrap()
+    }
 
-        let msg = ExecuteMsg::Claim {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
+    #[test]
+    fn test_exec_start_dutch_auction_invalid() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let receive = |custom_msg: Cw721CustomMsg| {
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: DUMMY_TOKEN_OWNER.to_owned(),
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                msg: to_json_binary(&custom_msg).unwrap(),
+            })
         };
 
-        let info = mock_info("any_user", &[]);
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
-        let transfer_nft_msg = Cw721ExecuteMsg::TransferNft {
-            recipient: "sender".to_string(),
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+        let msg = receive(Cw721CustomMsg::StartDutchAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            start_price: Uint128::from(200u128),
+            end_price: Uint128::from(200u128),
+            decay: PriceDecay::Linear,
+        });
+        let res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_ADDR, &[]), msg);
+        assert_eq!(ContractError::InvalidPrice {}, res.unwrap_err());
+
+        let msg = receive(Cw721CustomMsg::StartDutchAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            start_price: Uint128::from(1000u128),
+            end_price: Uint128::from(200u128),
+            decay: PriceDecay::Stepwise { interval: 0 },
+        });
+        let res = execute(deps.as_mut(), env, mock_info(DUMMY_TOKEN_ADDR, &[]), msg);
+        assert_eq!(ContractError::InvalidPriceDecay {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_query_current_price() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_dutch_auction(deps.as_mut(), PriceDecay::Linear);
+
+        env.block.time = Timestamp::from_seconds(50);
+        assert_eq!(Uint128::from(1000u128), current_price(deps.as_ref(), env.clone()));
+        env.block.time = Timestamp::from_seconds(125);
+        assert_eq!(Uint128::from(800u128), current_price(deps.as_ref(), env.clone()));
+        env.block.time = Timestamp::from_seconds(300);
+        assert_eq!(Uint128::from(200u128), current_price(deps.as_ref(), env.clone()));
+
+        // Drops of 200 every 25 seconds.
+        let mut deps = custom_mock_dependencies(&[]);
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+        start_dutch_auction(deps.as_mut(), PriceDecay::Stepwise { interval: 25000 });
+
+        env.block.time = Timestamp::from_seconds(124);
+        assert_eq!(Uint128::from(1000u128), current_price(deps.as_ref(), env.clone()));
+        env.block.time = Timestamp::from_seconds(160);
+        assert_eq!(Uint128::from(600u128), current_price(deps.as_ref(), env.clone()));
+
+        // English auctions have no computed price.
+        start_auction(deps.as_mut(), None);
+        let query_msg = QueryMsg::CurrentPrice {
+            auction_id: Uint128::from(2u128),
+        };
+        let res = query(deps.as_ref(), env, query_msg);
+        assert_eq!(ContractError::NotDutchAuction {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_buy_dutch_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_dutch_auction(deps.as_mut(), PriceDecay::Linear);
+
+        let msg = ExecuteMsg::BuyNowById {
+            auction_id: Uint128::from(1u128),
+        };
+        env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("buyer", &coins(500, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(
+            ContractError::PriceNotMet {
+                price: Uint128::from(600u128)
+            },
+            res.unwrap_err()
+        );
+
+        let proxy_msg = ExecuteMsg::PlaceProxyBid {
+            auction_id: Uint128::from(1u128),
         };
+        let info = mock_info("buyer", &coins(1000, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info, proxy_msg);
+        assert_eq!(ContractError::ProxyBidNotSupported {}, res.unwrap_err());
+
+        // A plain bid buys too; anything above the current price is refunded.
+        let bid_msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info("buyer", &coins(700, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info, bid_msg).unwrap();
         assert_eq!(
             Response::new()
                 .add_message(CosmosMsg::Bank(BankMsg::Send {
                     to_address: DUMMY_TOKEN_OWNER.to_owned(),
-                    amount: coins(100, "usd"),
+                    amount: coins(600, "usd"),
                 }))
                 .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                    contract_addr: DUMMY_TOKEN_ADDR.to_string(),
-                    msg: to_binary(&transfer_nft_msg).unwrap(),
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "buyer".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
                     funds: vec![],
                 }))
-                .add_attribute("action", "claim")
-                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
-                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
-                .add_attribute("recipient", "sender")
-                .add_attribute("winning_bid_amount", Uint128::from(100u128))
-                .add_attribute("auction_id", "1"),
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "buyer".to_string(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_attributes(vec![
+                    attr("action", "buy_now"),
+                    attr("token_id", DUMMY_UNCLAIMED_TOKEN),
+                    attr("bider", "buyer"),
+                    attr("amount", "600"),
+                ]),
             res
         );
+
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert!(state.is_claimed);
+        let info = mock_info("late", &coins(1000, "usd"));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
+    }
+
+    fn start_sealed_auction(deps: DepsMut, pricing: SealedPricing, forfeit_unrevealed: bool) {
+        start_auction_with(
+            deps,
+            Cw721CustomMsg::StartSealedAuction {
+                start_time: 100000,
+                duration: 100000,
+                reveal_duration: 50000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: Some(Uint128::from(100u128)),
+                pricing,
+                forfeit_unrevealed,
+            },
+        );
+    }
+
+    fn commit_bid(deps: DepsMut, bidder: &str, deposit: u128, amount: u128) -> Result<Response, ContractError> {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::CommitBid {
+            auction_id: Uint128::from(1u128),
+            commitment: Binary::from(Sha256::digest(format!("1:{}:{}:salt", bidder, amount).as_bytes()).to_vec()),
+        };
+        execute(deps, env, mock_info(bidder, &coins(deposit, "usd")), msg)
+    }
+
+    fn reveal_bid_msg(amount: u128, salt: &str) -> ExecuteMsg {
+        ExecuteMsg::RevealBid {
+            auction_id: Uint128::from(1u128),
+            amount: Uint128::from(amount),
+            salt: salt.to_string(),
+        }
     }
 
     #[test]
-    fn test_exec_claim_auction_not_ended() {
+    fn test_exec_sealed_auction_second_price() {
         let mut deps = custom_mock_dependencies(&[]);
         let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        start_auction(deps.as_mut(), None);
+        start_sealed_auction(deps.as_mut(), SealedPricing::SecondPrice, false);
 
-        let msg = ExecuteMsg::PlaceBid {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
-        };
+        let res = commit_bid(deps.as_mut(), "bidder_a", 50, 50);
+        assert_eq!(
+            ContractError::BidLowerThanMinBid {
+                min_bid: Uint128::from(100u128)
+            },
+            res.unwrap_err()
+        );
+        let _res = commit_bid(deps.as_mut(), "bidder_a", 500, 400).unwrap();
+        let res = commit_bid(deps.as_mut(), "bidder_a", 500, 400);
+        assert_eq!(ContractError::BidAlreadyCommitted {}, res.unwrap_err());
+        let _res = commit_bid(deps.as_mut(), "bidder_b", 300, 300).unwrap();
+        let _res = commit_bid(deps.as_mut(), "bidder_c", 200, 200).unwrap();
 
+        // Open bids are not accepted on a sealed auction.
         env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder_d", &coins(1000, "usd")), msg);
+        assert_eq!(ContractError::SealedBidRequired {}, res.unwrap_err());
 
-        let info = mock_info("sender", &coins(100, "usd".to_string()));
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("bidder_a", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), reveal_bid_msg(400, "salt"));
+        assert_eq!(ContractError::RevealNotStarted {}, res.unwrap_err());
 
-        let msg = ExecuteMsg::Claim {
-            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        env.block.time = Timestamp::from_seconds(210);
+        let msg = ExecuteMsg::CommitBid {
+            auction_id: Uint128::from(1u128),
+            commitment: Binary::from(vec![0u8; 32]),
         };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder_d", &coins(1000, "usd")), msg);
+        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), reveal_bid_msg(400, "wrong"));
+        assert_eq!(ContractError::InvalidReveal {}, res.unwrap_err());
 
-        let info = mock_info("any_user", &[]);
-        let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(ContractError::AuctionNotEnded {}, res.unwrap_err());
+        // The part of the deposit above the revealed amount is refunded.
+        let res = execute(deps.as_mut(), env.clone(), info, reveal_bid_msg(400, "salt")).unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bidder_a".to_string(),
+                amount: coins(100, "usd"),
+            })],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+        // A lower bid is credited back in full once revealed.
+        let info = mock_info("bidder_b", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, reveal_bid_msg(300, "salt")).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(Uint128::from(300u128), balance(deps.as_ref(), "bidder_b")[0].amount);
+
+        let bids_msg = QueryMsg::Bids {
+            auction_id: Uint128::from(1u128),
+            start_after: None,
+            limit: None,
+            order_by: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), bids_msg.clone());
+        assert_eq!(ContractError::BidsSealed {}, res.unwrap_err());
+
+        // The winner pays the second highest bid; bidder_c never revealed and
+        // gets the deposit back. Both refunds are credited.
+        env.block.time = Timestamp::from_seconds(260);
+        let msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(300, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "bidder_a".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+
+        assert_eq!(Uint128::from(200u128), balance(deps.as_ref(), "bidder_c")[0].amount);
+        assert_eq!(Uint128::from(100u128), balance(deps.as_ref(), "bidder_a")[0].amount);
+
+        let bids: Vec<Bid> = from_json(query(deps.as_ref(), env, bids_msg).unwrap()).unwrap();
+        assert_eq!(2, bids.len());
     }
 
     #[test]
-    fn test_exec_claim_auction_already_claimed() {
+    fn test_exec_sealed_auction_copied_commitment() {
         let mut deps = custom_mock_dependencies(&[]);
-        let env = mock_env();
+        let mut env = mock_env();
         let info = mock_info("owner", &[]);
-        let msg = InstantiateMsg {};
-        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        let hook_msg = Cw721CustomMsg::StartAuction {
-            start_time: 100000,
-            duration: 100000,
-            coin_denom: "usd".to_string(),
-            min_bid: None,
+        start_sealed_auction(deps.as_mut(), SealedPricing::FirstPrice, false);
+        let _res = commit_bid(deps.as_mut(), "bidder_a", 500, 400).unwrap();
+
+        // bidder_b copies bidder_a's commitment from the chain.
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::CommitBid {
+            auction_id: Uint128::from(1u128),
+            commitment: Binary::from(Sha256::digest("1:bidder_a:400:salt".as_bytes()).to_vec()),
         };
-        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
-            sender: DUMMY_TOKEN_OWNER.to_owned(),
-            token_id: "claimed_token".to_string(),
-            msg: to_binary(&hook_msg).unwrap(),
-        });
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(0u64);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("bidder_b", &coins(500, "usd")), msg).unwrap();
 
-        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        env.block.time = Timestamp::from_seconds(210);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder_b", &[]), reveal_bid_msg(400, "salt"));
+        assert_eq!(ContractError::InvalidReveal {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env, mock_info("bidder_a", &[]), reveal_bid_msg(400, "salt")).unwrap();
+    }
 
-        // Auction is over.
-        env.block.time = Timestamp::from_seconds(300);
+    #[test]
+    fn test_exec_sealed_auction_unrevealed() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
 
-        let msg = ExecuteMsg::Claim {
-            token_id: "claimed_token".to_string(),
-            token_address: DUMMY_TOKEN_ADDR.to_string(),
-        };
+        start_sealed_auction(deps.as_mut(), SealedPricing::FirstPrice, true);
+        let _res = commit_bid(deps.as_mut(), "bidder_a", 500, 400).unwrap();
+        let _res = commit_bid(deps.as_mut(), "bidder_b", 300, 300).unwrap();
 
-        let info = mock_info("any_user", &[]);
-        let res = execute(deps.as_mut(), env, info, msg);
-        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+        // Nobody revealed: the seller keeps the deposits and gets the NFT back.
+        env.block.time = Timestamp::from_seconds(260);
+        let msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(800, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
     }
 
     #[test]
-    fn test_query_start_auction() {
-        let mut deps = mock_dependencies();
+    fn test_exec_cancel_sealed_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_sealed_auction(deps.as_mut(), SealedPricing::FirstPrice, true);
+        let _res = commit_bid(deps.as_mut(), "bidder_a", 500, 400).unwrap();
+
+        let msg = ExecuteMsg::CancelAuctionById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        env.block.time = Timestamp::from_seconds(210);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
+
+        // Deposits are refunded even when unrevealed bids would be forfeited.
+        env.block.time = Timestamp::from_seconds(150);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(Uint128::from(500u128), balance(deps.as_ref(), "bidder_a")[0].amount);
+    }
+
+    fn cw20_bid(deps: DepsMut, env: Env, token_contract: &str, bidder: &str, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: bidder.to_owned(),
+            amount: Uint128::from(amount),
+            msg: to_json_binary(&Cw20HookMsg::PlaceBid {
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                token_address: DUMMY_TOKEN_ADDR.to_string(),
+            })
+            .unwrap(),
+        });
+        execute(deps, env, mock_info(token_contract, &[]), msg)
+    }
+
+    #[test]
+    fn test_exec_place_bid_cw20() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Cw20 { contract_addr: "cw20_token".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        // Tokens of another cw20 contract are rejected.
+        let res = cw20_bid(deps.as_mut(), env.clone(), "other_cw20_token", "sender", 100);
+        assert_eq!(
+            ContractError::InvalidFunds {
+                msg: "No cw20_token assets are provided to auction".to_string(),
+            },
+            res.unwrap_err()
+        );
+
+        let _res = cw20_bid(deps.as_mut(), env.clone(), "cw20_token", "sender", 100).unwrap();
+
+        // The outbid funds stay committed to the auction.
+        let res = cw20_bid(deps.as_mut(), env.clone(), "cw20_token", "other", 200).unwrap();
+        assert!(res.messages.is_empty());
+
+        // Native funds are not accepted for a cw20 auction.
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let info = mock_info("native_bidder", &coins(300, "cw20_token"));
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(
+            ContractError::InvalidFunds {
+                msg: "No cw20_token assets are provided to auction".to_string(),
+            },
+            res.unwrap_err()
+        );
+
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("any_user", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: DUMMY_TOKEN_OWNER.to_string(),
+                    amount: Uint128::from(200u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            res.messages[0].msg
+        );
+
+        // Losing bids are retracted in cw20 tokens.
+        assert_eq!(
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "sender".to_string(),
+                    amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })],
+            retract_bid(deps.as_mut(), "sender")
+        );
+    }
+
+    #[test]
+    fn test_exec_place_bid_min_increment() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: None,
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: Some(BidIncrement {
+                    amount: Uint128::from(5u128),
+                    bps: 1000,
+                }),
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+
+        // 10% of the highest bid is larger than the absolute increment.
+        let info = mock_info("other", &coins(105, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(
+            ContractError::BidIncrementTooSmall {
+                min_required: Uint128::from(110u128)
+            },
+            res.unwrap_err()
+        );
+
+        let info = mock_info("other", &coins(110, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_exec_place_bid_default_min_increment() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.default_min_increment = Some(BidIncrement {
+            amount: Uint128::from(50u128),
+            bps: 0,
+        });
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+
+        let info = mock_info("other", &coins(120, "usd".to_string()));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(
+            ContractError::BidIncrementTooSmall {
+                min_required: Uint128::from(150u128)
+            },
+            res.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_exec_place_bid_invalid_coins() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+        check_auction_created(deps.as_ref(), None);
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let error = ContractError::InvalidFunds {
+            msg: "Auctions require exactly one coin to be sent.".to_string(),
+        };
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_string(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        // No coins
+        let info = mock_info("sender", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(error, res.unwrap_err());
+
+        // Multiple coins
+        let info = mock_info("sender", &[coin(100, "usd"), coin(100, "uluna")]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(error, res.unwrap_err());
+
+        let error = ContractError::InvalidFunds {
+            msg: "No usd assets are provided to auction".to_string(),
+        };
+
+        // Invalid denom
+        let info = mock_info("sender", &[coin(100, "uluna")]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(error, res.unwrap_err());
+
+        // Correct denom
+        let info = mock_info("sender", &[coin(0, "usd")]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(error, res.unwrap_err());
+    }
+    #[test]
+    fn test_exec_start_auction_start_time_in_past() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+
+        assert_eq!(
+            ContractError::InvalidStartTime {
+                current_time: env.block.time.nanos() / 1000000,
+                current_block: env.block.height,
+            },
+            res.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_exec_start_auction_zero_start_time() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 0,
+            duration: 1,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_start_auction_zero_duration() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100,
+            duration: 0,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_start_auction_reserve_below_min_bid() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: Some(Uint128::from(100u128)),
+            reserve_price: Some(Uint128::from(50u128)),
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        assert_eq!(ContractError::InvalidReservePrice {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_update_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction(deps.as_mut(), None);
+        env.block.time = Timestamp::from_seconds(50);
+
+        let update_msg = |start_time, duration, min_bid, reserve_price| ExecuteMsg::UpdateAuction {
+            auction_id: Uint128::from(1u128),
+            start_time,
+            duration,
+            asset: Some(AssetInfo::Native { denom: "eur".to_string() }),
+            min_bid,
+            reserve_price,
+            buy_now_price: None,
+        };
+        let msg = update_msg(Some(120000), Some(50000), Some(Uint128::from(100u128)), Some(Uint128::from(200u128)));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+
+        // Same checks as when starting an auction.
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DUMMY_TOKEN_OWNER, &[]),
+            update_msg(None, None, Some(Uint128::from(100u128)), Some(Uint128::from(50u128))),
+        );
+        assert_eq!(ContractError::InvalidReservePrice {}, res.unwrap_err());
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DUMMY_TOKEN_OWNER, &[]),
+            update_msg(Some(10000), None, None, None),
+        );
+        assert!(matches!(res.unwrap_err(), ContractError::InvalidStartTime { .. }));
+        // Durations running past the largest timestamp are rejected.
+        for start_time in [None, Some(120000)] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DUMMY_TOKEN_OWNER, &[]),
+                update_msg(start_time, Some(u64::MAX), None, None),
+            );
+            assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+        }
+
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_OWNER, &[]), msg).unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(AssetInfo::Native { denom: "eur".to_string() }, state.asset);
+        assert_eq!(Some(Uint128::from(100u128)), state.min_bid);
+        assert_eq!(Expiration::AtTime(Timestamp::from_seconds(120)), state.start_time);
+        assert_eq!(Expiration::AtTime(Timestamp::from_seconds(170)), state.end_time);
+
+        // Once started, the end can still be moved.
+        env.block.time = Timestamp::from_seconds(130);
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(DUMMY_TOKEN_OWNER, &[]),
+            update_msg(None, Some(100000), None, None),
+        )
+        .unwrap();
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap();
+        assert_eq!(Expiration::AtTime(Timestamp::from_seconds(220)), state.end_time);
+
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "eur")), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(DUMMY_TOKEN_OWNER, &[]),
+            update_msg(None, None, Some(Uint128::from(50u128)), None),
+        );
+        assert_eq!(ContractError::AuctionHasBids {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_cancel_no_bids() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::CancelAuction {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        assert_eq!(
+            Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                    recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                    token_id: DUMMY_UNCLAIMED_TOKEN.to_owned()
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            res
+        );
+
+        assert!(
+            TOKEN_AUCTION_STATE
+                .load(deps.as_ref().storage, 1u128)
+                .unwrap()
+                .is_cancelled
+        );
+    }
+
+    #[test]
+    fn test_exec_cancel_with_bids() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info("outbid", &coins(50, "usd"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        let info = mock_info("bidder", &coins(100, "usd"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::CancelAuction {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned()
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })),
+            res
+        );
+        // The bid is credited to the bidder's balance.
+        assert_eq!(
+            vec![Asset {
+                info: AssetInfo::Native { denom: "usd".to_string() },
+                amount: Uint128::from(100u128),
+            }],
+            balance(deps.as_ref(), "bidder")
+        );
+        let withdraw = |amount: u128| ExecuteMsg::Withdraw {
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            amount: Uint128::from(amount),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder", &[]), withdraw(150));
+        assert_eq!(
+            ContractError::InsufficientBalance {
+                balance: Uint128::from(100u128)
+            },
+            res.unwrap_err()
+        );
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder", &[]), withdraw(100)).unwrap();
+        assert!(balance(deps.as_ref(), "bidder").is_empty());
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bidder".to_string(),
+                amount: coins(100, "usd"),
+            })],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+        // The outbid bidder takes their funds back from the cancelled auction.
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "outbid".to_string(),
+                amount: coins(50, "usd"),
+            })],
+            retract_bid(deps.as_mut(), "outbid")
+        );
+
+        assert!(
+            TOKEN_AUCTION_STATE
+                .load(deps.as_ref().storage, 1u128)
+                .unwrap()
+                .is_cancelled
+        );
+    }
+
+    #[test]
+    fn test_exec_cancel_not_token_owner() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::CancelAuction {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_cancel_ended_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::CancelAuction {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(300);
+
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionEnded {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_claim_no_bids() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        env.block.time = Timestamp::from_seconds(250);
+
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "claim")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("recipient", DUMMY_TOKEN_OWNER)
+                .add_attribute("winning_bid_amount", Uint128::zero())
+                .add_attribute("auction_id", "1"),
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_claim() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(250);
+
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let transfer_nft_msg = Cw721ExecuteMsg::TransferNft {
+            recipient: "sender".to_string(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+        };
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_string(),
+                    msg: to_json_binary(&transfer_nft_msg).unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "claim")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("recipient", "sender")
+                .add_attribute("winning_bid_amount", Uint128::from(100u128))
+                .add_attribute("auction_id", "1"),
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_claim_with_fee() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.fee_bps = 250;
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info("sender", &coins(1000, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(250);
+
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "fee_collector".to_string(),
+                    amount: coins(25, "usd"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(975, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_string(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "sender".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    fn relist_msg(auction_id: u128, start_time: u64) -> ExecuteMsg {
+        ExecuteMsg::Relist {
+            auction_id: Uint128::from(auction_id),
+            start_time,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        }
+    }
+
+    #[test]
+    fn test_exec_relist() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        env.block.time = Timestamp::from_seconds(150);
+        let res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_OWNER, &[]), relist_msg(1, 300000));
+        assert_eq!(ContractError::AuctionNotEnded {}, res.unwrap_err());
+
+        env.block.time = Timestamp::from_seconds(250);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), relist_msg(1, 300000));
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+
+        // The token stays in escrow and gets a new auction id.
+        let res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_OWNER, &[]), relist_msg(1, 300000)).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            vec![Uint128::from(1u128), Uint128::from(2u128)],
+            auction_infos()
+                .load(
+                    &deps.storage,
+                    (&Addr::unchecked(DUMMY_TOKEN_ADDR), DUMMY_UNCLAIMED_TOKEN)
+                )
+                .unwrap()
+                .auction_ids
+        );
+        let state = TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 2u128).unwrap();
+        assert_eq!(DUMMY_TOKEN_OWNER, state.owner);
+        assert_eq!(Expiration::AtTime(Timestamp::from_seconds(300)), state.start_time);
+
+        // The old auction can no longer be claimed.
+        let claim_msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), claim_msg);
+        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+
+        // Sold auctions are claimed, not relisted.
+        env.block.time = Timestamp::from_seconds(350);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(2u128),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, "usd")), msg).unwrap();
+        env.block.time = Timestamp::from_seconds(450);
+        let res = execute(deps.as_mut(), env, mock_info(DUMMY_TOKEN_OWNER, &[]), relist_msg(2, 500000));
+        assert_eq!(ContractError::AuctionSold {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_claim_reserve_not_met() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction_with(
+            deps.as_mut(),
+            Cw721CustomMsg::StartAuction {
+                start_time: 100000,
+                duration: 100000,
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                min_bid: None,
+                reserve_price: Some(Uint128::from(500u128)),
+                buy_now_price: None,
+                extension_window: None,
+                extension_duration: None,
+                min_increment: None,
+                accepted_assets: None,
+                allowlist: None,
+            },
+        );
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        // With auto-send on, the refund is sent rather than credited.
+        let auto_send_msg = ExecuteMsg::SetAutoSend { enabled: true };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("sender", &[]), auto_send_msg).unwrap();
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(250);
+
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "sender".to_string(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_attribute("action", "claim")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("recipient", DUMMY_TOKEN_OWNER)
+                .add_attribute("reserve_met", "false")
+                .add_attribute("auction_id", "1"),
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_claim_auction_not_ended() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        env.block.time = Timestamp::from_seconds(150);
+
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionNotEnded {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_claim_auction_already_claimed() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let msg = mock_instantiate_msg();
+        let _res = instantiate(deps.as_mut(), env, info, msg).unwrap();
+
+        let hook_msg = Cw721CustomMsg::StartAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: "claimed_token".to_string(),
+            msg: to_json_binary(&hook_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0u64);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // The token has already left the contract.
+        deps.querier.set_token_owner("claimed_token", DUMMY_TOKEN_OWNER);
+
+        // Auction is over.
+        env.block.time = Timestamp::from_seconds(300);
+
+        let msg = ExecuteMsg::Claim {
+            token_id: "claimed_token".to_string(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+
+        let info = mock_info("any_user", &[]);
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+    }
+
+    fn add_to_bundle(deps: DepsMut, token_address: &str, token_id: &str) {
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: token_id.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::AddToBundle {}).unwrap(),
+        });
+        let _res = execute(deps, mock_env(), mock_info(token_address, &[]), msg).unwrap();
+    }
+
+    fn start_bundle_auction(deps: DepsMut, seller: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::StartBundleAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
+        };
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        execute(deps, env, mock_info(seller, &[]), msg)
+    }
+
+    fn bundle_transfer_msgs(recipient: &str) -> Vec<CosmosMsg> {
+        [(DUMMY_TOKEN_ADDR, "token_a"), (DUMMY_ROYALTY_TOKEN_ADDR, "token_b")]
+            .into_iter()
+            .map(|(token_address, token_id)| {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: token_address.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: recipient.to_owned(),
+                        token_id: token_id.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_exec_bundle_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        add_to_bundle(deps.as_mut(), DUMMY_TOKEN_ADDR, "token_a");
+        add_to_bundle(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, "token_b");
+        let bundle = vec![
+            BundleItem {
+                token_address: DUMMY_TOKEN_ADDR.to_owned(),
+                token_id: "token_a".to_owned(),
+            },
+            BundleItem {
+                token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+                token_id: "token_b".to_owned(),
+            },
+        ];
+        let draft_msg = QueryMsg::BundleDraft {
+            seller: DUMMY_TOKEN_OWNER.to_owned(),
+        };
+        let res: Vec<BundleItem> = from_json(query(deps.as_ref(), env.clone(), draft_msg.clone()).unwrap()).unwrap();
+        assert_eq!(bundle, res);
+
+        let res = start_bundle_auction(deps.as_mut(), "other");
+        assert_eq!(ContractError::EmptyBundle {}, res.unwrap_err());
+        // A collection denied after its token joined the draft blocks the start.
+        add_collection(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, CollectionStatus::Denied, None);
+        let res = start_bundle_auction(deps.as_mut(), DUMMY_TOKEN_OWNER);
+        assert_eq!(
+            ContractError::CollectionNotAllowed {
+                collection: DUMMY_ROYALTY_TOKEN_ADDR.to_string()
+            },
+            res.unwrap_err()
+        );
+        add_collection(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, CollectionStatus::Allowed, None);
+        let _res = start_bundle_auction(deps.as_mut(), DUMMY_TOKEN_OWNER).unwrap();
+
+        let res: Vec<BundleItem> = from_json(query(deps.as_ref(), env.clone(), draft_msg).unwrap()).unwrap();
+        assert!(res.is_empty());
+        let query_msg = QueryMsg::Bundle {
+            auction_id: Uint128::from(1u128),
+        };
+        let res: Vec<BundleItem> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(bundle, res);
+
+        // Any token of the bundle finds its auction.
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: "token_b".to_owned(),
+            token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("bidder", &coins(1000, "usd")), msg).unwrap();
+
+        // token_b carries a 10% royalty on its half of the price.
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        let mut expected = vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_ROYALTY_RECIPIENT.to_owned(),
+                amount: coins(50, "usd"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(950, "usd"),
+            }),
+        ];
+        expected.extend(bundle_transfer_msgs("bidder"));
+        assert_eq!(
+            expected,
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    #[test]
+    fn test_exec_cancel_bundle() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        add_to_bundle(deps.as_mut(), DUMMY_TOKEN_ADDR, "token_a");
+        add_to_bundle(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, "token_b");
+        let msg = ExecuteMsg::CancelBundleDraft {};
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            bundle_transfer_msgs(DUMMY_TOKEN_OWNER),
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(ContractError::EmptyBundle {}, res.unwrap_err());
+
+        add_to_bundle(deps.as_mut(), DUMMY_TOKEN_ADDR, "token_a");
+        add_to_bundle(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, "token_b");
+        let _res = start_bundle_auction(deps.as_mut(), DUMMY_TOKEN_OWNER).unwrap();
+
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::CancelAuction {
+            token_id: "token_b".to_owned(),
+            token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            bundle_transfer_msgs(DUMMY_TOKEN_OWNER),
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    #[test]
+    fn test_exec_private_bundle_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        add_to_bundle(deps.as_mut(), DUMMY_TOKEN_ADDR, "token_a");
+        add_to_bundle(deps.as_mut(), DUMMY_ROYALTY_TOKEN_ADDR, "token_b");
+        let msg = ExecuteMsg::StartBundleAuction {
+            start_time: 100000,
+            duration: 100000,
+            asset: AssetInfo::Native { denom: "usd".to_string() },
+            min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: Some(BidderAllowlist::Addresses {
+                addresses: vec![Addr::unchecked("sender")],
+            }),
+        };
+        env.block.time = Timestamp::from_seconds(0);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info(DUMMY_TOKEN_OWNER, &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        env.block.time = Timestamp::from_seconds(150);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &coins(100, "usd")), msg.clone());
+        assert_eq!(ContractError::BidderNotAllowed {}, res.unwrap_err());
+        let _res = execute(deps.as_mut(), env, mock_info("sender", &coins(100, "usd")), msg).unwrap();
+    }
+
+    fn list_for_sale(deps: DepsMut, seller: &str, token_id: &str, expires: Option<u64>) {
+        let custom_msg = Cw721CustomMsg::ListForSale {
+            price: Uint128::from(100u128),
+            denom: "usd".to_string(),
+            expires,
+        };
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: seller.to_owned(),
+            token_id: token_id.to_owned(),
+            msg: to_json_binary(&custom_msg).unwrap(),
+        });
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0u64);
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let _res = execute(deps, env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_exec_list_for_sale() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN, Some(100000));
+
+        let query_msg = QueryMsg::Listing {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let res: Listing = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(
+            Listing {
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                token_address: DUMMY_TOKEN_ADDR.to_owned(),
+                seller: DUMMY_TOKEN_OWNER.to_owned(),
+                price: Uint128::from(100u128),
+                denom: "usd".to_string(),
+                expires: Expiration::AtTime(Timestamp::from_seconds(100)),
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_buy() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN, None);
+
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+
+        let info = mock_info("buyer", &coins(50, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(
+            ContractError::InvalidFunds {
+                msg: "Exactly 100usd must be sent to buy".to_string(),
+            },
+            res.unwrap_err()
+        );
+
+        let info = mock_info(DUMMY_TOKEN_OWNER, &coins(100, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(ContractError::SellerCannotBuy {}, res.unwrap_err());
+
+        let info = mock_info("buyer", &coins(100, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "buyer".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "buy")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("recipient", "buyer")
+                .add_attribute("price", Uint128::from(100u128)),
+            res
+        );
+
+        // Listing is gone once bought.
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::ListingDoesNotExist {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_buy_with_royalty() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.fee_bps = 250;
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::ListForSale {
+                price: Uint128::from(1000u128),
+                denom: "usd".to_string(),
+                expires: None,
+            })
+            .unwrap(),
+        });
+        let info = mock_info(DUMMY_ROYALTY_TOKEN_ADDR, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+        };
+        let info = mock_info("buyer", &coins(1000, "usd"));
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "fee_collector".to_string(),
+                    amount: coins(25, "usd"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_ROYALTY_RECIPIENT.to_owned(),
+                    amount: coins(100, "usd"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(875, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "buyer".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    #[test]
+    fn test_exec_buy_with_invalid_royalty_recipient() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_INVALID_ROYALTY_TOKEN.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::ListForSale {
+                price: Uint128::from(1000u128),
+                denom: "usd".to_string(),
+                expires: None,
+            })
+            .unwrap(),
+        });
+        let info = mock_info(DUMMY_ROYALTY_TOKEN_ADDR, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The sale goes through without the royalty.
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_INVALID_ROYALTY_TOKEN.to_owned(),
+            token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+        };
+        let info = mock_info("buyer", &coins(1000, "usd"));
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(1000, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "buyer".to_string(),
+                        token_id: DUMMY_INVALID_ROYALTY_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    #[test]
+    fn test_exec_buy_without_proceeds() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let mut msg = mock_instantiate_msg();
+        msg.fee_bps = 9000;
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::ListForSale {
+                price: Uint128::from(1000u128),
+                denom: "usd".to_string(),
+                expires: None,
+            })
+            .unwrap(),
+        });
+        let info = mock_info(DUMMY_ROYALTY_TOKEN_ADDR, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The fee and the royalty take the whole price: the seller gets no
+        // zero-amount transfer.
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+        };
+        let info = mock_info("buyer", &coins(1000, "usd"));
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "fee_collector".to_owned(),
+                    amount: coins(900, "usd"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_ROYALTY_RECIPIENT.to_owned(),
+                    amount: coins(100, "usd"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_ROYALTY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "buyer".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ],
+            res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<CosmosMsg>>()
+        );
+    }
+
+    #[test]
+    fn test_exec_buy_expired_listing() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN, Some(100000));
+
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::Buy {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let info = mock_info("buyer", &coins(100, "usd"));
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::ListingExpired {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_update_price() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN, None);
+
+        let msg = ExecuteMsg::UpdatePrice {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            price: Uint128::from(200u128),
+        };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(ContractError::Unauthorized {}, res.unwrap_err());
+
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let query_msg = QueryMsg::Listing {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let res: Listing = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(Uint128::from(200u128), res.price);
+    }
+
+    #[test]
+    fn test_exec_delist() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, DUMMY_UNCLAIMED_TOKEN, None);
+
+        let msg = ExecuteMsg::Delist {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: DUMMY_TOKEN_OWNER.to_owned(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "delist")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR),
+            res
+        );
+
+        let query_msg = QueryMsg::Listing {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let res = query(deps.as_ref(), env, query_msg);
+        assert_eq!(ContractError::ListingDoesNotExist {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_query_listings() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, "a_token", None);
+        list_for_sale(deps.as_mut(), DUMMY_TOKEN_OWNER, "b_token", None);
+        list_for_sale(deps.as_mut(), "other_owner", "c_token", None);
+
+        let token_ids = |query_msg| {
+            let res: Vec<Listing> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+            res.into_iter().map(|listing| listing.token_id).collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            vec!["a_token", "b_token", "c_token"],
+            token_ids(QueryMsg::Listings {
+                token_address: None,
+                seller: None,
+                start_after: None,
+                limit: None,
+            })
+        );
+        assert_eq!(
+            vec!["b_token"],
+            token_ids(QueryMsg::Listings {
+                token_address: None,
+                seller: Some(DUMMY_TOKEN_OWNER.to_owned()),
+                start_after: Some((DUMMY_TOKEN_ADDR.to_owned(), "a_token".to_owned())),
+                limit: None,
+            })
+        );
+        assert_eq!(
+            vec!["b_token", "c_token"],
+            token_ids(QueryMsg::Listings {
+                token_address: Some(DUMMY_TOKEN_ADDR.to_owned()),
+                seller: None,
+                start_after: Some((DUMMY_TOKEN_ADDR.to_owned(), "a_token".to_owned())),
+                limit: Some(10),
+            })
+        );
+        assert_eq!(
+            Vec::<String>::new(),
+            token_ids(QueryMsg::Listings {
+                token_address: Some("other_token_addr".to_owned()),
+                seller: Some(DUMMY_TOKEN_OWNER.to_owned()),
+                start_after: None,
+                limit: None,
+            })
+        );
+    }
+
+    fn make_offer(deps: DepsMut, bidder: &str, token_id: &str, amount: u128, expires: Option<u64>) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::MakeOffer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            token_id: token_id.to_owned(),
+            expires,
+        };
+        execute(deps, mock_env(), mock_info(bidder, &coins(amount, "usd")), msg)
+    }
+
+    fn accept_offer_msg(bidder: &str) -> ExecuteMsg {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::AcceptOffer {
+                bidder: bidder.to_owned(),
+            })
+            .unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_exec_make_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let res = make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 0, None);
+        assert_eq!(
+            ContractError::InvalidFunds {
+                msg: "Offers require a non-zero amount.".to_string(),
+            },
+            res.unwrap_err()
+        );
+        // mock_env's block time is well past 1000ms.
+        let res = make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 100, Some(1000));
+        assert_eq!(ContractError::InvalidExpiration {}, res.unwrap_err());
+
+        let res = make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 100, None).unwrap();
+        assert_eq!(
+            Response::new().add_attributes(vec![
+                attr("action", "make_offer"),
+                attr("token_id", DUMMY_UNCLAIMED_TOKEN),
+                attr("token_contract", DUMMY_TOKEN_ADDR),
+                attr("bidder", "bidder"),
+                attr("amount", "100"),
+                attr("asset", "usd"),
+                attr("expires", "expiration: never"),
+            ]),
+            res
+        );
+        let res = make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 200, None);
+        assert_eq!(ContractError::OfferAlreadyExists {}, res.unwrap_err());
+
+        // Offers can also be escrowed in cw20 tokens.
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "cw20_bidder".to_owned(),
+            amount: Uint128::from(300u128),
+            msg: to_json_binary(&Cw20HookMsg::MakeOffer {
+                token_address: DUMMY_TOKEN_ADDR.to_owned(),
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                expires: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("cw20_token", &[]), msg).unwrap();
+
+        let query_msg = QueryMsg::Offer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            bidder: "cw20_bidder".to_owned(),
+        };
+        let res: Offer = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(
+            Offer {
+                token_address: DUMMY_TOKEN_ADDR.to_owned(),
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                bidder: "cw20_bidder".to_owned(),
+                asset: Asset {
+                    info: AssetInfo::Cw20 { contract_addr: "cw20_token".to_string() },
+                    amount: Uint128::from(300u128),
+                },
+                expires: Expiration::Never {},
+            },
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_cancel_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 100, None).unwrap();
+
+        let msg = ExecuteMsg::CancelOffer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), msg.clone());
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bidder", &[]), msg.clone()).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bidder".to_owned(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_attribute("action", "cancel_offer")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("bidder", "bidder"),
+            res
+        );
+        let res = execute(deps.as_mut(), env, mock_info("bidder", &[]), msg);
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_accept_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let expires = env.block.time.plus_seconds(100).nanos() / 1_000_000;
+        make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 100, Some(expires)).unwrap();
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept_offer_msg("other"));
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept_offer_msg("bidder")).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "bidder".to_string(),
+                        token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "accept_offer")
+                .add_attribute("token_id", DUMMY_UNCLAIMED_TOKEN)
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("seller", DUMMY_TOKEN_OWNER)
+                .add_attribute("bidder", "bidder")
+                .add_attribute("amount", "100"),
+            res
+        );
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept_offer_msg("bidder"));
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+
+        make_offer(deps.as_mut(), "bidder", DUMMY_UNCLAIMED_TOKEN, 100, Some(expires)).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        let res = execute(deps.as_mut(), env, info, accept_offer_msg("bidder"));
+        assert_eq!(ContractError::OfferExpired {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_query_offers() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        make_offer(deps.as_mut(), "alice", "a_token", 100, None).unwrap();
+        make_offer(deps.as_mut(), "bob", "a_token", 100, None).unwrap();
+        make_offer(deps.as_mut(), "alice", "b_token", 100, None).unwrap();
+
+        let offers = |query_msg| {
+            let res: Vec<Offer> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+            res.into_iter().map(|offer| (offer.token_id, offer.bidder)).collect::<Vec<(String, String)>>()
+        };
+        let pair = |token_id: &str, bidder: &str| (token_id.to_owned(), bidder.to_owned());
+
+        assert_eq!(
+            vec![pair("a_token", "alice"), pair("b_token", "alice")],
+            offers(QueryMsg::Offers {
+                token_address: None,
+                token_id: None,
+                bidder: Some("alice".to_owned()),
+                start_after: None,
+                limit: None,
+            })
+        );
+        assert_eq!(
+            vec![pair("a_token", "bob")],
+            offers(QueryMsg::Offers {
+                token_address: Some(DUMMY_TOKEN_ADDR.to_owned()),
+                token_id: Some("a_token".to_owned()),
+                bidder: None,
+                start_after: Some((DUMMY_TOKEN_ADDR.to_owned(), "a_token".to_owned(), "alice".to_owned())),
+                limit: None,
+            })
+        );
+        assert_eq!(
+            vec![pair("a_token", "bob"), pair("b_token", "alice")],
+            offers(QueryMsg::Offers {
+                token_address: Some(DUMMY_TOKEN_ADDR.to_owned()),
+                token_id: None,
+                bidder: None,
+                start_after: Some((DUMMY_TOKEN_ADDR.to_owned(), "a_token".to_owned(), "alice".to_owned())),
+                limit: Some(10),
+            })
+        );
+        assert_eq!(
+            vec![pair("a_token", "alice")],
+            offers(QueryMsg::Offers {
+                token_address: None,
+                token_id: None,
+                bidder: None,
+                start_after: None,
+                limit: Some(1),
+            })
+        );
+    }
+
+    fn make_collection_offer(deps: DepsMut, bidder: &str, quantity: u32, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::MakeCollectionOffer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            price: Uint128::from(50u128),
+            quantity,
+            expires: None,
+        };
+        execute(deps, mock_env(), mock_info(bidder, &coins(amount, "usd")), msg)
+    }
+
+    fn accept_collection_offer_msg(token_id: &str, bidder: &str) -> ExecuteMsg {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: DUMMY_TOKEN_OWNER.to_owned(),
+            token_id: token_id.to_owned(),
+            msg: to_json_binary(&Cw721CustomMsg::AcceptCollectionOffer {
+                bidder: bidder.to_owned(),
+            })
+            .unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_exec_make_collection_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        let res = make_collection_offer(deps.as_mut(), "bidder", 0, 0);
+        assert_eq!(ContractError::InvalidQuantity {}, res.unwrap_err());
+        let res = make_collection_offer(deps.as_mut(), "bidder", 3, 100);
+        assert_eq!(
+            ContractError::InvalidFunds {
+                msg: "Exactly 150usd must be sent for the offer".to_string(),
+            },
+            res.unwrap_err()
+        );
+
+        make_collection_offer(deps.as_mut(), "bidder", 3, 150).unwrap();
+        let res = make_collection_offer(deps.as_mut(), "bidder", 1, 50);
+        assert_eq!(ContractError::OfferAlreadyExists {}, res.unwrap_err());
+        make_collection_offer(deps.as_mut(), "other", 1, 50).unwrap();
+
+        let query_msg = QueryMsg::CollectionOffers {
+            token_address: Some(DUMMY_TOKEN_ADDR.to_owned()),
+            bidder: None,
+            start_after: Some((DUMMY_TOKEN_ADDR.to_owned(), "bidder".to_owned())),
+            limit: None,
+        };
+        let res: Vec<CollectionOffer> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(
+            vec![CollectionOffer {
+                token_address: DUMMY_TOKEN_ADDR.to_owned(),
+                bidder: "other".to_owned(),
+                asset: AssetInfo::Native { denom: "usd".to_string() },
+                price: Uint128::from(50u128),
+                quantity: 1,
+                expires: Expiration::Never {},
+            }],
+            res
+        );
+
+        let query_msg = QueryMsg::CollectionOffers {
+            token_address: None,
+            bidder: Some("bidder".to_owned()),
+            start_after: None,
+            limit: None,
+        };
+        let res: Vec<CollectionOffer> = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(vec![3], res.into_iter().map(|offer| offer.quantity).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_exec_accept_collection_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        make_collection_offer(deps.as_mut(), "bidder", 2, 100).unwrap();
+
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept_collection_offer_msg("a_token", "bidder")).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                    amount: coins(50, "usd"),
+                }))
+                .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: DUMMY_TOKEN_ADDR.to_owned(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: "bidder".to_string(),
+                        token_id: "a_token".to_owned(),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+                .add_attribute("action", "accept_collection_offer")
+                .add_attribute("token_id", "a_token")
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("seller", DUMMY_TOKEN_OWNER)
+                .add_attribute("bidder", "bidder")
+                .add_attribute("price", "50")
+                .add_attribute("remaining_quantity", "1"),
+            res
+        );
+
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), accept_collection_offer_msg("b_token", "bidder")).unwrap();
+        // The offer is used up.
+        let res = execute(deps.as_mut(), env.clone(), info, accept_collection_offer_msg("c_token", "bidder"));
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+        let query_msg = QueryMsg::CollectionOffer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+            bidder: "bidder".to_owned(),
+        };
+        let res = query(deps.as_ref(), env, query_msg);
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_cancel_collection_offer() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        make_collection_offer(deps.as_mut(), "bidder", 3, 150).unwrap();
+        let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, accept_collection_offer_msg("a_token", "bidder")).unwrap();
+
+        let msg = ExecuteMsg::CancelCollectionOffer {
+            token_address: DUMMY_TOKEN_ADDR.to_owned(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), msg.clone());
+        assert_eq!(ContractError::OfferDoesNotExist {}, res.unwrap_err());
+
+        // Only the two unfilled units are refunded.
+        let res = execute(deps.as_mut(), env, mock_info("bidder", &[]), msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_message(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "bidder".to_owned(),
+                    amount: coins(100, "usd"),
+                }))
+                .add_attribute("action", "cancel_collection_offer")
+                .add_attribute("token_contract", DUMMY_TOKEN_ADDR)
+                .add_attribute("bidder", "bidder"),
+            res
+        );
+    }
+
+    #[test]
+    fn test_exec_claim_by_id_twice() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info("any_user", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert!(TOKEN_AUCTION_STATE.load(deps.as_ref().storage, 1u128).unwrap().is_claimed);
+
+        let res = execute(deps.as_mut(), env, info, msg);
+        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_exec_by_id_targets_older_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+
+        start_auction(deps.as_mut(), None);
+
+        env.block.time = Timestamp::from_seconds(50);
+        let msg = ExecuteMsg::CancelAuctionById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info(DUMMY_TOKEN_OWNER, &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        // The token comes back for a second auction.
+        start_auction(deps.as_mut(), None);
+
+        // Auction 1 cannot be cancelled again, bid on or claimed.
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(ContractError::AuctionCancelled {}, res.unwrap_err());
+
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let info = mock_info("sender", &coins(100, "usd".to_string()));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(ContractError::AuctionCancelled {}, res.unwrap_err());
+
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(2u128),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(3u128),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(ContractError::AuctionDoesNotExist {}, res.unwrap_err());
+
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(1u128),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("any_user", &[]), msg);
+        assert_eq!(ContractError::AuctionCancelled {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_instantiate_sets_contract_version() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg()).unwrap();
+
+        let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+        assert_eq!(CONTRACT_NAME, version.contract);
+        assert_eq!(CONTRACT_VERSION, version.version);
+    }
+
+    #[test]
+    fn test_migrate_same_version() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg()).unwrap();
+
+        // Migrations for versions at or below the stored one are skipped.
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_attribute("action", "migrate")
+                .add_attribute("from_version", CONTRACT_VERSION)
+                .add_attribute("to_version", CONTRACT_VERSION),
+            res
+        );
+    }
+
+    #[test]
+    fn test_migrate_downgrade() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg()).unwrap();
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default());
+        assert_eq!(
+            ContractError::MigrationDowngrade {
+                stored: "99.0.0".to_string(),
+                current: CONTRACT_VERSION.to_string(),
+            },
+            res.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_migrate_other_contract() {
+        let mut deps = custom_mock_dependencies(&[]);
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default());
+        assert_eq!(
+            ContractError::InvalidMigrationContract {
+                contract: "crates.io:cw20-base".to_string(),
+            },
+            res.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_migrate_collections() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, mock_instantiate_msg()).unwrap();
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+        LEGACY_CONFIG
+            .save(
+                deps.as_mut().storage,
+                &LegacyConfig {
+                    admin: Addr::unchecked("owner"),
+                    fee_collector: Addr::unchecked("fee_collector"),
+                    fee_bps: 0,
+                    allowed_assets: vec![],
+                    allowed_collections: vec![Addr::unchecked(DUMMY_TOKEN_ADDR)],
+                    default_min_increment: None,
+                },
+            )
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_attribute("action", "migrate")
+                .add_attribute("from_version", "0.2.0")
+                .add_attribute("to_version", CONTRACT_VERSION)
+                .add_attribute("migrated_collections", "1")
+                .add_attribute("migrated_committed_bids", "0"),
+            res
+        );
+
+        let config: Config = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(config.restrict_collections);
+        let query_msg = QueryMsg::Collection {
+            address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res: CollectionInfo = from_json(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(CollectionStatus::Allowed, res.status);
+    }
+
+    #[test]
+    fn test_migrate_collections_without_config() {
+        let mut deps = custom_mock_dependencies(&[]);
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default());
+        assert_eq!(ContractError::MigrationConfigRequired {}, res.unwrap_err());
+    }
+
+    #[test]
+    fn test_migrate_committed_bids() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let mut env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+        start_auction(deps.as_mut(), None);
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBidById {
+            auction_id: Uint128::from(1u128),
+        };
+        let _res = execute(deps.as_mut(), env, mock_info("bidder", &coins(100, "usd")), msg).unwrap();
+
+        // Bids placed before 0.4.0 were only recorded on the auction state.
+        COMMITTED_BIDS.remove(deps.as_mut().storage, (1u128, &Addr::unchecked("bidder")));
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.3.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_attribute("action", "migrate")
+                .add_attribute("from_version", "0.3.0")
+                .add_attribute("to_version", CONTRACT_VERSION)
+                .add_attribute("migrated_committed_bids", "1"),
+            res
+        );
+        assert_eq!(Uint128::from(100u128), committed_bid(deps.as_ref(), "bidder"));
+    }
+
+    #[test]
+    fn test_migrate_auction_infos() {
+        let mut deps = custom_mock_dependencies(&[]);
+        let env = mock_env();
+        let info = mock_info("owner", &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, mock_instantiate_msg()).unwrap();
+        // Deployments holding the legacy keys predate cw2 and the config.
+        cw2::CONTRACT.remove(deps.as_mut().storage);
+        CONFIG.remove(deps.as_mut().storage);
+
+        // Token "1" on "2abc" and token "12" on "abc" share the legacy key
+        // "12abc", so that entry mixes the ids of both tokens.
+        let storage = deps.as_mut().storage;
+        save_baseline_auction(storage, 1, "2abc", "1", "", 0);
+        save_baseline_auction(storage, 2, "abc", "12", "", 0);
+        save_baseline_auction(storage, 3, "2abc", "1", "", 0);
+        save_baseline_auction(storage, 4, DUMMY_TOKEN_ADDR, DUMMY_UNCLAIMED_TOKEN, "", 0);
+        NEXT_AUCTION_ID.save(storage, &Uint128::from(5u128)).unwrap();
+        legacy_auction_infos()
+            .save(
+                storage,
+                "12abc",
+                &AuctionInfo {
+                    auction_ids: vec![Uint128::from(1u128), Uint128::from(2u128), Uint128::from(3u128)],
+                    token_address: "abc".to_string(),
+                    token_id: "12".to_string(),
+                },
+            )
+            .unwrap();
+        legacy_auction_infos()
+            .save(
+                storage,
+                &(DUMMY_UNCLAIMED_TOKEN.to_owned() + DUMMY_TOKEN_ADDR),
+                &AuctionInfo {
+                    auction_ids: vec![Uint128::from(4u128)],
+                    token_address: DUMMY_TOKEN_ADDR.to_string(),
+                    token_id: DUMMY_UNCLAIMED_TOKEN.to_string(),
+                },
+            )
+            .unwrap();
+
+        // Their config is seeded from the migrate message.
+        let migrate_msg = MigrateMsg {
+            admin: Some("owner".to_string()),
+            fee_collector: None,
+            fee_bps: Some(250),
+        };
+        let res = migrate(deps.as_mut(), env.clone(), migrate_msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_attribute("action", "migrate")
+                .add_attribute("from_version", "0.1.0")
+                .add_attribute("to_version", CONTRACT_VERSION)
+                .add_attribute("migrated_auction_states", "4")
+                .add_attribute("migrated_auction_infos", "3")
+                .add_attribute("migrated_collections", "0")
+                .add_attribute("migrated_committed_bids", "0"),
+            res
+        );
+        assert_eq!(
+            CONTRACT_VERSION,
+            cw2::get_contract_version(deps.as_ref().storage).unwrap().version
+        );
+        let config: Config = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(Addr::unchecked("owner"), config.admin);
+        assert_eq!(Addr::unchecked("owner"), config.fee_collector);
+        assert_eq!(250, config.fee_bps);
+
+        assert!(legacy_auction_infos()
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .next()
+            .is_none());
+        let auction_ids = |token_address: &str, token_id: &str| {
+            auction_infos()
+                .load(deps.as_ref().storage, (&Addr::unchecked(token_address), token_id))
+                .unwrap()
+                .auction_ids
+        };
+        assert_eq!(vec![Uint128::from(1u128), Uint128::from(3u128)], auction_ids("2abc", "1"));
+        assert_eq!(vec![Uint128::from(2u128)], auction_ids("abc", "12"));
+        let query_msg = QueryMsg::AuctionInfos {
+            token_address: Some(DUMMY_TOKEN_ADDR.to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let res: Vec<AuctionInfo> = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        assert_eq!(
+            vec![AuctionInfo {
+                auction_ids: vec![Uint128::from(4u128)],
+                token_address: DUMMY_TOKEN_ADDR.to_string(),
+                token_id: DUMMY_UNCLAIMED_TOKEN.to_string(),
+            }],
+            res
+        );
+
+        // Auctions started after the migration append to the migrated entry.
+        start_auction(deps.as_mut(), None);
+        assert_eq!(
+            vec![Uint128::from(4u128), Uint128::from(5u128)],
+            auction_infos()
+                .load(
+                    deps.as_ref().storage,
+                    (&Addr::unchecked(DUMMY_TOKEN_ADDR), DUMMY_UNCLAIMED_TOKEN)
+                )
+                .unwrap()
+                .auction_ids
+        );
+    }
+
+    /// Writes an ended auction state as the first release stored it.
+    fn save_baseline_auction(
+        storage: &mut dyn Storage,
+        auction_id: u128,
+        token_address: &str,
+        token_id: &str,
+        high_bidder: &str,
+        high_bid: u128,
+    ) {
+        let state = format!(
+            r#"{{"start_time":{{"at_time":"100000000000"}},"end_time":{{"at_time":"200000000000"}},"high_bidder_addr":"{}","high_bidder_amount":"{}","coin_denom":"usd","auction_id":"{}","min_bid":null,"owner":"dummy_token_owner","token_id":"{}","token_address":"{}","is_cancelled":false}}"#,
+            high_bidder, high_bid, auction_id, token_id, token_address
+        );
+        storage.set(&TOKEN_AUCTION_STATE.key(auction_id), state.as_bytes());
+    }
+
+    #[test]
+    fn test_migrate_baseline_state() {
+        // Storage as written by the first release: no cw2 info, no config,
+        // auction states priced in a `coin_denom`.
+        let mut deps = custom_mock_dependencies(&[]);
+        let storage = deps.as_mut().storage;
+        storage.set(b"next_auction_id", br#""2""#);
+        storage.set(
+            &TOKEN_AUCTION_STATE.key(1u128),
+            br#"{"start_time":{"at_time":"100000000000"},"end_time":{"at_time":"200000000000"},"high_bidder_addr":"bidder","high_bidder_amount":"100","coin_denom":"usd","auction_id":"1","min_bid":null,"owner":"dummy_token_owner","token_id":"dummy_unclaimed_token","token_address":"dummy_token_addr","is_cancelled":false}"#,
+        );
+        storage.set(
+            &BIDS.key(1u128),
+            br#"[{"bidder":"bidder","amount":"100","timestamp":"150000000000"}]"#,
+        );
+        legacy_auction_infos()
+            .save(
+                storage,
+                &(DUMMY_UNCLAIMED_TOKEN.to_owned() + DUMMY_TOKEN_ADDR),
+                &AuctionInfo {
+                    auction_ids: vec![Uint128::from(1u128)],
+                    token_address: DUMMY_TOKEN_ADDR.to_string(),
+                    token_id: DUMMY_UNCLAIMED_TOKEN.to_string(),
+                },
+            )
+            .unwrap();
+
+        let migrate_msg = MigrateMsg {
+            admin: Some("owner".to_string()),
+            fee_collector: None,
+            fee_bps: None,
+        };
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg).unwrap();
+        assert_eq!(
+            Response::new()
+                .add_attribute("action", "migrate")
+                .add_attribute("from_version", "0.1.0")
+                .add_attribute("to_version", CONTRACT_VERSION)
+                .add_attribute("migrated_auction_states", "1")
+                .add_attribute("migrated_auction_infos", "1")
+                .add_attribute("migrated_collections", "0")
+                .add_attribute("migrated_committed_bids", "1"),
+            res
+        );
+
+        let query_msg = QueryMsg::AuctionState {
+            auction_id: Uint128::from(1u128),
+        };
+        let state: TokenAuctionState = from_json(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(AssetInfo::Native { denom: "usd".to_string() }, state.asset);
+        assert_eq!(Uint128::from(100u128), state.high_bidder_amount);
+        assert_eq!(Uint128::from(100u128), committed_bid(deps.as_ref(), "bidder"));
+
+        // The live auction keeps taking bids and settles.
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(150);
+        let msg = ExecuteMsg::PlaceBid {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("other", &coins(150, "usd")), msg).unwrap();
+        env.block.time = Timestamp::from_seconds(250);
+        let msg = ExecuteMsg::Claim {
+            token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
+            token_address: DUMMY_TOKEN_ADDR.to_string(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(150, "usd"),
+            }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn test_migrate_baseline_claimed_auctions() {
+        let mut deps = custom_mock_dependencies(&[]);
+        // The first release sent the NFT on claim and had no claim flag.
+        deps.querier.set_token_owner("sold_token", "bidder");
+        let storage = deps.as_mut().storage;
+        storage.set(b"next_auction_id", br#""4""#);
+        // Auction 1 was claimed, then the token came back for auction 2.
+        save_baseline_auction(storage, 1, DUMMY_TOKEN_ADDR, DUMMY_UNCLAIMED_TOKEN, "bidder", 100);
+        save_baseline_auction(storage, 2, DUMMY_TOKEN_ADDR, DUMMY_UNCLAIMED_TOKEN, "other", 150);
+        // Auction 3 was claimed and the token left the contract.
+        save_baseline_auction(storage, 3, DUMMY_TOKEN_ADDR, "sold_token", "bidder", 200);
+
+        let migrate_msg = MigrateMsg {
+            admin: Some("owner".to_string()),
+            fee_collector: None,
+            fee_bps: None,
+        };
+        let res = migrate(deps.as_mut(), mock_env(), migrate_msg).unwrap();
+        assert!(res.attributes.contains(&attr("migrated_committed_bids", "1")));
+        let is_claimed = |auction_id: u128| TOKEN_AUCTION_STATE.load(deps.as_ref().storage, auction_id).unwrap().is_claimed;
+        assert!(is_claimed(1));
+        assert!(!is_claimed(2));
+        assert!(is_claimed(3));
+
+        // Only the live auction's high bid is committed; the settled ones
+        // hold no funds to raise or withdraw.
+        let committed = |auction_id: u128, bidder: &str| {
+            let query_msg = QueryMsg::CommittedBid {
+                auction_id: Uint128::from(auction_id),
+                bidder: bidder.to_owned(),
+            };
+            from_json::<Uint128>(query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap()
+        };
+        assert!(committed(1, "bidder").is_zero());
+        assert_eq!(Uint128::from(150u128), committed(2, "other"));
+        assert!(committed(3, "bidder").is_zero());
+
+        let claim = |auction_id: u128| ExecuteMsg::ClaimById {
+            auction_id: Uint128::from(auction_id),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), claim(1));
+        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), claim(3));
+        assert_eq!(ContractError::AuctionAlreadyClaimed {}, res.unwrap_err());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), claim(2)).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: DUMMY_TOKEN_OWNER.to_owned(),
+                amount: coins(150, "usd"),
+            }),
+            res.messages[0].msg
+        );
+    }
+
+    #[test]
+    fn test_query_start_auction() {
+        let mut deps = custom_mock_dependencies(&[]);
         let env = mock_env();
         let info = mock_info("owner", &[]);
-        let _res = instantiate(deps.as_mut(), env, info, InstantiateMsg {}).unwrap();
+        let _res = instantiate(deps.as_mut(), env, info, mock_instantiate_msg()).unwrap();
 
         let custom_msg = Cw721CustomMsg::StartAuction {
             start_time: 100000,
             duration: 100000,
-            coin_denom: "usd".to_string(),
+            asset: AssetInfo::Native { denom: "usd".to_string() },
             min_bid: None,
+            reserve_price: None,
+            buy_now_price: None,
+            extension_window: None,
+            extension_duration: None,
+            min_increment: None,
+            accepted_assets: None,
+            allowlist: None,
         };
         let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
             sender: DUMMY_TOKEN_OWNER.to_owned(),
             token_id: DUMMY_UNCLAIMED_TOKEN.to_owned(),
-            msg: to_binary(&custom_msg).unwrap(),
+            msg: to_json_binary(&custom_msg).unwrap(),
         });
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(0u64);
@@ -809,7 +4703,7 @@ mod tests {
         let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
             sender: "foo_token_owner".to_owned(),
             token_id: "foo_token".to_owned(),
-            msg: to_binary(&custom_msg).unwrap(),
+            msg: to_json_binary(&custom_msg).unwrap(),
         });
 
         let info = mock_info(DUMMY_TOKEN_ADDR, &[]);
@@ -819,10 +4713,10 @@ mod tests {
 
         let query_msg = QueryMsg::AuctionInfos {
             token_address: Some(DUMMY_TOKEN_ADDR.to_string()),
-            start_after: Some("e".to_string()),
+            start_after: Some((DUMMY_TOKEN_ADDR.to_string(), "e".to_string())),
             limit: Some(10),
         };
-        let res:Vec<AuctionInfo> = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        let res:Vec<AuctionInfo> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
         assert_eq!(
             vec! [ AuctionInfo {
                     auction_ids: vec![Uint128::from(2u128)],
@@ -836,10 +4730,10 @@ mod tests {
         
         let query_msg = QueryMsg::AuctionInfos {
             token_address: Some(DUMMY_TOKEN_ADDR.to_string()),
-            start_after: Some("g".to_string()),
+            start_after: Some((DUMMY_TOKEN_ADDR.to_string(), "g".to_string())),
             limit: Some(10),
         };
-        let res:Vec<AuctionInfo> = from_binary(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        let res:Vec<AuctionInfo> = from_json(query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
         assert_eq!(
             Vec::<AuctionInfo>::new(),
             res
@@ -850,7 +4744,7 @@ mod tests {
             start_after: None,
             limit: Some(10),
         };
-        let res:Vec<AuctionInfo> = from_binary(&query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+        let res:Vec<AuctionInfo> = from_json(query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
         assert_eq!(
             vec! [ AuctionInfo {
                     auction_ids: vec![Uint128::from(1u128)],
@@ -1,14 +1,23 @@
 use cosmwasm_std::{
-    from_binary, from_slice,
+    from_json,
     testing::{mock_env, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR},
-    to_binary, Binary, Coin, ContractResult, OwnedDeps, Querier,
+    to_json_binary, Binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
     QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
 };
-use cw721::{Cw721QueryMsg, OwnerOfResponse};
+use cw721::{Cw721QueryMsg, OwnerOfResponse, TokensResponse};
+use std::collections::HashMap;
+use crate::msg::{Cw2981ExtensionMsg, Cw2981QueryMsg, OracleQueryMsg, PriceResponse, RoyaltiesInfoResponse};
 
 pub const DUMMY_TOKEN_ADDR: &str = "dummy_token_addr";
+// A collection implementing cw2981 with a 10% royalty.
+pub const DUMMY_ROYALTY_TOKEN_ADDR: &str = "dummy_royalty_token_addr";
+pub const DUMMY_ROYALTY_RECIPIENT: &str = "dummy_royalty_recipient";
+// A token of the royalty collection whose recipient is not a valid address.
+pub const DUMMY_INVALID_ROYALTY_TOKEN: &str = "dummy_invalid_royalty_token";
 pub const DUMMY_TOKEN_OWNER: &str = "dummy_token_owner";
 pub const DUMMY_UNCLAIMED_TOKEN: &str = "dummy_unclaimed_token";
+// A price oracle valuing every asset at twice the reference asset.
+pub const DUMMY_ORACLE_ADDR: &str = "dummy_oracle_addr";
 
 pub fn custom_mock_dependencies(
     contract_balance: &[Coin],
@@ -26,12 +35,14 @@ pub fn custom_mock_dependencies(
 
 pub struct WasmMockQuerier {
     base: MockQuerier,
+    // Tokens not listed here are owned by the contract.
+    token_owners: HashMap<String, String>,
 }
 
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         // MockQuerier doesn't support Custom, so we ignore it completely here
-        let request: QueryRequest<cosmwasm_std::Empty> = match from_slice(bin_request) {
+        let request: QueryRequest<cosmwasm_std::Empty> = match from_json(bin_request) {
             Ok(v) => v,
             Err(e) => {
                 return SystemResult::Err(SystemError::InvalidRequest {
@@ -50,28 +61,77 @@ impl WasmMockQuerier {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
                 match contract_addr.as_str() {
                     DUMMY_TOKEN_ADDR => self.handle_token_query(msg),
-                    _ => panic!("Unknown Contract Address {}", contract_addr),
+                    DUMMY_ROYALTY_TOKEN_ADDR => match from_json(msg) {
+                        Ok(royalty_query) => self.handle_royalty_query(royalty_query),
+                        Err(_) => self.handle_token_query(msg),
+                    },
+                    DUMMY_ORACLE_ADDR => self.handle_oracle_query(from_json(msg).unwrap()),
+                    _ => SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.to_string(),
+                    }),
                 }
             }
             _ => self.base.handle_query(request),
         }
     }
 
+    fn handle_royalty_query(&self, msg: Cw2981QueryMsg) -> QuerierResult {
+        match msg {
+            Cw2981QueryMsg::Extension {
+                msg: Cw2981ExtensionMsg::RoyaltyInfo { token_id, sale_price },
+            } => {
+                let address = match token_id.as_str() {
+                    DUMMY_INVALID_ROYALTY_TOKEN => "Invalid Recipient",
+                    _ => DUMMY_ROYALTY_RECIPIENT,
+                };
+                let res = RoyaltiesInfoResponse {
+                    address: address.to_owned(),
+                    royalty_amount: sale_price.multiply_ratio(10u128, 100u128),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+        }
+    }
+
+    fn handle_oracle_query(&self, msg: OracleQueryMsg) -> QuerierResult {
+        match msg {
+            OracleQueryMsg::Price { .. } => {
+                let res = PriceResponse {
+                    rate: Decimal::from_ratio(2u128, 1u128),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+        }
+    }
+
     fn handle_token_query(&self, msg: &Binary) -> QuerierResult {
-        match from_binary(msg).unwrap() {
+        // Collections without the cw2981 extension reject the royalty query.
+        let msg = match from_json(msg) {
+            Ok(msg) => msg,
+            Err(e) => return SystemResult::Ok(ContractResult::Err(e.to_string())),
+        };
+        match msg {
             Cw721QueryMsg::OwnerOf { token_id, .. } => {
-                let res = if token_id == DUMMY_UNCLAIMED_TOKEN {
-                    OwnerOfResponse {
-                        owner: mock_env().contract.address.to_string(),
-                        approvals: vec![],
-                    }
-                } else {
-                    OwnerOfResponse {
-                        owner: DUMMY_TOKEN_OWNER.to_owned(),
-                        approvals: vec![],
-                    }
+                let owner = match self.token_owners.get(&token_id) {
+                    Some(owner) => owner.clone(),
+                    None => mock_env().contract.address.to_string(),
+                };
+                let res = OwnerOfResponse {
+                    owner,
+                    approvals: vec![],
                 };
-                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+            Cw721QueryMsg::Tokens { owner, .. } => {
+                let mut tokens: Vec<String> = self
+                    .token_owners
+                    .iter()
+                    .filter(|(_, token_owner)| **token_owner == owner)
+                    .map(|(token_id, _)| token_id.clone())
+                    .collect();
+                tokens.sort();
+                let res = TokensResponse { tokens };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
             }
 
             _ => panic!("Unsupported Query"),
@@ -79,6 +139,13 @@ impl WasmMockQuerier {
     }
 
     pub fn new(base: MockQuerier<cosmwasm_std::Empty>) -> Self {
-        WasmMockQuerier { base }
+        WasmMockQuerier {
+            base,
+            token_owners: HashMap::new(),
+        }
+    }
+
+    pub fn set_token_owner(&mut self, token_id: &str, owner: &str) {
+        self.token_owners.insert(token_id.to_owned(), owner.to_owned());
     }
 }
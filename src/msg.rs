@@ -1,38 +1,235 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Uint128};
-use crate::state::{OrderBy, AuctionInfo, TokenAuctionState, Bid};
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use crate::state::{OrderBy, Asset, AssetInfo, AuctionInfo, BidIncrement, BidderAllowlist, BundleItem, CollectionInfo, CollectionOffer, CollectionStatus, Config, PriceDecay, PriceSource, SealedPricing, TokenAuctionState, Bid, Listing, Offer};
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Defaults to the instantiating address.
+    pub admin: Option<String>,
+    /// Defaults to the admin.
+    pub fee_collector: Option<String>,
+    pub fee_bps: u64,
+    pub allowed_assets: Vec<AssetInfo>,
+    pub restrict_collections: bool,
+    pub default_min_increment: Option<BidIncrement>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// Seeds the config of deployments that predate it. Required for those,
+    /// ignored otherwise.
+    pub admin: Option<String>,
+    /// Defaults to the admin.
+    pub fee_collector: Option<String>,
+    pub fee_bps: Option<u64>,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
     ReceiveNft(cw721::Cw721ReceiveMsg),
+    Receive(cw20::Cw20ReceiveMsg),
+    /// Bids on the latest auction of the token.
     PlaceBid {
         token_id: String,
         token_address: String,
     },
+    PlaceBidById {
+        auction_id: Uint128,
+    },
+    /// Escrows the sent funds, on top of any committed ones, as a maximum the
+    /// contract bids up to in minimum increments against competing bids.
+    PlaceProxyBid {
+        auction_id: Uint128,
+    },
+    /// Takes back the funds committed to an auction the sender is not winning,
+    /// while it runs or once it is closed, cancelled or claimed.
+    RetractBid {
+        auction_id: Uint128,
+    },
+    /// Commits to a sealed bid. The funds sent are held as a deposit and must
+    /// cover the amount revealed later.
+    CommitBid {
+        auction_id: Uint128,
+        /// sha256 of `"<auction_id>:<bidder>:<amount>:<salt>"`.
+        commitment: Binary,
+    },
+    RevealBid {
+        auction_id: Uint128,
+        amount: Uint128,
+        salt: String,
+    },
+    /// Auctions every token in the sender's bundle draft as a single lot.
+    StartBundleAuction {
+        start_time: u64,
+        duration: u64,
+        asset: AssetInfo,
+        min_bid: Option<Uint128>,
+        reserve_price: Option<Uint128>,
+        buy_now_price: Option<Uint128>,
+        extension_window: Option<u64>,
+        extension_duration: Option<u64>,
+        min_increment: Option<BidIncrement>,
+        accepted_assets: Option<Vec<AssetInfo>>,
+        allowlist: Option<BidderAllowlist>,
+    },
+    /// Returns every token in the sender's bundle draft.
+    CancelBundleDraft {},
+    /// Changes an English auction that has no bid yet. Only the seller can
+    /// update it; omitted fields are left unchanged.
+    UpdateAuction {
+        auction_id: Uint128,
+        start_time: Option<u64>,
+        duration: Option<u64>,
+        asset: Option<AssetInfo>,
+        min_bid: Option<Uint128>,
+        reserve_price: Option<Uint128>,
+        buy_now_price: Option<Uint128>,
+    },
+    /// Starts a new English auction for the tokens of an ended, unsold auction
+    /// while they stay in escrow.
+    Relist {
+        auction_id: Uint128,
+        start_time: u64,
+        duration: u64,
+        asset: AssetInfo,
+        min_bid: Option<Uint128>,
+        reserve_price: Option<Uint128>,
+        buy_now_price: Option<Uint128>,
+        extension_window: Option<u64>,
+        extension_duration: Option<u64>,
+        min_increment: Option<BidIncrement>,
+        accepted_assets: Option<Vec<AssetInfo>>,
+        allowlist: Option<BidderAllowlist>,
+    },
+    /// Cancels the latest auction of the token.
     CancelAuction {
         token_id: String,
         token_address: String,
     },
+    CancelAuctionById {
+        auction_id: Uint128,
+    },
+    /// Claims the latest auction of the token.
     Claim {
         token_id: String,
         token_address: String,
     },
+    ClaimById {
+        auction_id: Uint128,
+    },
+    /// Buys the latest auction of the token at its buy now price.
+    BuyNow {
+        token_id: String,
+        token_address: String,
+    },
+    BuyNowById {
+        auction_id: Uint128,
+    },
+    Buy {
+        token_id: String,
+        token_address: String,
+    },
+    UpdatePrice {
+        token_id: String,
+        token_address: String,
+        price: Uint128,
+    },
+    Delist {
+        token_id: String,
+        token_address: String,
+    },
+    UpdateConfig {
+        admin: Option<String>,
+        fee_collector: Option<String>,
+        fee_bps: Option<u64>,
+        allowed_assets: Option<Vec<AssetInfo>>,
+        restrict_collections: Option<bool>,
+        default_min_increment: Option<BidIncrement>,
+        price_source: Option<PriceSource>,
+    },
+    /// Sets how many units of `reference` one unit of `asset` is worth for the
+    /// rate table price source. `None` removes the rate.
+    SetRate {
+        asset: AssetInfo,
+        reference: AssetInfo,
+        rate: Option<Decimal>,
+    },
+    /// Escrows the sent funds as an offer on a token, listed or not.
+    MakeOffer {
+        token_address: String,
+        token_id: String,
+        /// Expiration in milliseconds. The offer never expires if omitted.
+        expires: Option<u64>,
+    },
+    /// Withdraws the sender's offer and refunds the escrow.
+    CancelOffer {
+        token_address: String,
+        token_id: String,
+    },
+    /// Escrows `price * quantity` to buy up to `quantity` tokens of a collection.
+    MakeCollectionOffer {
+        token_address: String,
+        price: Uint128,
+        quantity: u32,
+        /// Expiration in milliseconds. The offer never expires if omitted.
+        expires: Option<u64>,
+    },
+    /// Withdraws the sender's collection offer and refunds what is left in escrow.
+    CancelCollectionOffer {
+        token_address: String,
+    },
+    /// Withdraws refunds credited to the sender's balance.
+    Withdraw {
+        asset: AssetInfo,
+        amount: Uint128,
+    },
+    /// Sends future refunds to the sender straight away instead of crediting them.
+    SetAutoSend {
+        enabled: bool,
+    },
+    /// Adds a collection to the allow or deny list, replacing any existing entry.
+    AddCollection {
+        address: String,
+        status: CollectionStatus,
+        name: Option<String>,
+        fee_bps: Option<u64>,
+        allowed_assets: Option<Vec<AssetInfo>>,
+    },
+    RemoveCollection {
+        address: String,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
     #[returns(AuctionInfo)]
     AuctionInfos {
         token_address: Option<String>,
-        start_after: Option<String>,
+        /// (token_address, token_id) of the last auction info on the previous page.
+        start_after: Option<(String, String)>,
         limit: Option<u64>,
     },
     #[returns(TokenAuctionState)]
     AuctionState { auction_id: Uint128 },
+    /// Price a Dutch auction sells at in the current block.
+    #[returns(Uint128)]
+    CurrentPrice { auction_id: Uint128 },
+    /// Tokens sold by the auction. Regular auctions hold a single token.
+    #[returns(Vec<BundleItem>)]
+    Bundle { auction_id: Uint128 },
+    #[returns(Vec<BundleItem>)]
+    BundleDraft { seller: String },
+    /// Bidders allowed on the auction, `None` when anyone may bid.
+    #[returns(Option<BidderAllowlist>)]
+    BidderAllowlist { auction_id: Uint128 },
+    /// Funds the bidder has committed to the auction. The leading proxy bid
+    /// reports the price it reached, so its maximum stays hidden.
+    #[returns(Uint128)]
+    CommittedBid { auction_id: Uint128, bidder: String },
     #[returns(Vec<Bid>)]
     Bids {
         auction_id: Uint128,
@@ -40,6 +237,65 @@ pub enum QueryMsg {
         limit: Option<u64>,
         order_by: Option<OrderBy>,
     },
+    /// Refunds credited to the address and not withdrawn yet.
+    #[returns(Vec<Asset>)]
+    Balance { address: String },
+    /// Rate the configured price source gives for `asset` in `reference`.
+    #[returns(Decimal)]
+    Rate {
+        asset: AssetInfo,
+        reference: AssetInfo,
+    },
+    #[returns(CollectionInfo)]
+    Collection { address: String },
+    #[returns(Vec<CollectionInfo>)]
+    Collections {
+        status: Option<CollectionStatus>,
+        start_after: Option<String>,
+        limit: Option<u64>,
+    },
+    #[returns(Offer)]
+    Offer {
+        token_address: String,
+        token_id: String,
+        bidder: String,
+    },
+    /// `token_id` is only used together with `token_address`.
+    #[returns(Vec<Offer>)]
+    Offers {
+        token_address: Option<String>,
+        token_id: Option<String>,
+        bidder: Option<String>,
+        /// (token_address, token_id, bidder) of the last offer on the previous page.
+        start_after: Option<(String, String, String)>,
+        limit: Option<u64>,
+    },
+    #[returns(CollectionOffer)]
+    CollectionOffer {
+        token_address: String,
+        bidder: String,
+    },
+    #[returns(Vec<CollectionOffer>)]
+    CollectionOffers {
+        token_address: Option<String>,
+        bidder: Option<String>,
+        /// (token_address, bidder) of the last offer on the previous page.
+        start_after: Option<(String, String)>,
+        limit: Option<u64>,
+    },
+    #[returns(Listing)]
+    Listing {
+        token_id: String,
+        token_address: String,
+    },
+    #[returns(Vec<Listing>)]
+    Listings {
+        token_address: Option<String>,
+        seller: Option<String>,
+        /// (token_address, token_id) of the last listing on the previous page.
+        start_after: Option<(String, String)>,
+        limit: Option<u64>,
+    },
 }
 
 
@@ -48,7 +304,131 @@ pub enum Cw721CustomMsg {
     StartAuction {
         start_time: u64,
         duration: u64,
-        coin_denom: String,
+        asset: AssetInfo,
+        min_bid: Option<Uint128>,
+        /// Hidden floor checked at claim time. If the winning bid is below it,
+        /// the NFT goes back to the seller and the bidder is refunded.
+        reserve_price: Option<Uint128>,
+        /// Paying at least this amount ends the auction immediately.
+        buy_now_price: Option<Uint128>,
+        /// Milliseconds before the end in which a bid extends the auction.
+        /// Must be set together with `extension_duration`.
+        extension_window: Option<u64>,
+        /// Milliseconds the auction stays open after a bid inside the window.
+        extension_duration: Option<u64>,
+        /// Defaults to the marketplace's `default_min_increment`.
+        min_increment: Option<BidIncrement>,
+        /// Other assets bids may be placed in, valued in `asset` through the
+        /// configured price source.
+        accepted_assets: Option<Vec<AssetInfo>>,
+        /// Restricts bidding to the listed addresses or to holders of a collection.
+        allowlist: Option<BidderAllowlist>,
+    },
+    StartDutchAuction {
+        start_time: u64,
+        duration: u64,
+        asset: AssetInfo,
+        start_price: Uint128,
+        end_price: Uint128,
+        decay: PriceDecay,
+    },
+    /// Commit phase of `duration` milliseconds followed by a reveal phase of
+    /// `reveal_duration` milliseconds.
+    StartSealedAuction {
+        start_time: u64,
+        duration: u64,
+        reveal_duration: u64,
+        asset: AssetInfo,
+        /// Required for `SecondPrice` auctions.
         min_bid: Option<Uint128>,
+        pricing: SealedPricing,
+        forfeit_unrevealed: bool,
+    },
+    /// Adds the token to the sender's bundle draft, see `StartBundleAuction`.
+    AddToBundle {},
+    ListForSale {
+        price: Uint128,
+        denom: String,
+        /// Expiration in milliseconds. The listing never expires if omitted.
+        expires: Option<u64>,
+    },
+    /// Sells the token to `bidder` at their offer.
+    AcceptOffer {
+        bidder: String,
+    },
+    /// Sells the token to `bidder` at their collection offer price.
+    AcceptCollectionOffer {
+        bidder: String,
+    },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    PlaceBid {
+        token_id: String,
+        token_address: String,
+    },
+    PlaceBidById {
+        auction_id: Uint128,
     },
-}
\ No newline at end of file
+    BuyNow {
+        token_id: String,
+        token_address: String,
+    },
+    BuyNowById {
+        auction_id: Uint128,
+    },
+    PlaceProxyBid {
+        auction_id: Uint128,
+    },
+    MakeOffer {
+        token_address: String,
+        token_id: String,
+        expires: Option<u64>,
+    },
+    CommitBid {
+        auction_id: Uint128,
+        commitment: Binary,
+    },
+    MakeCollectionOffer {
+        token_address: String,
+        price: Uint128,
+        quantity: u32,
+        expires: Option<u64>,
+    },
+}
+
+/// Query a price oracle set as `PriceSource::Oracle` must answer.
+#[cw_serde]
+pub enum OracleQueryMsg {
+    Price {
+        asset: AssetInfo,
+        reference: AssetInfo,
+    },
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    /// Units of `reference` one unit of `asset` is worth.
+    pub rate: Decimal,
+}
+
+/// cw2981 royalty query, wrapped in the `Extension` variant cw721 contracts expose.
+#[cw_serde]
+pub enum Cw2981QueryMsg {
+    Extension { msg: Cw2981ExtensionMsg },
+}
+
+#[cw_serde]
+pub enum Cw2981ExtensionMsg {
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
@@ -1,4 +1,6 @@
-use cosmwasm_std::{Addr, Order, StdResult, Storage, Timestamp, Uint128};
+use std::fmt;
+use cosmwasm_std::{to_json_binary, coins, Addr, BankMsg, Binary, CosmosMsg, Decimal, Order, StdResult, Storage, Timestamp, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
 use cw721::Expiration;
 use cw_storage_plus::{Bound, Item, Map, IndexedMap, MultiIndex, Index, IndexList};
 use schemars::JsonSchema;
@@ -10,19 +12,152 @@ use crate::ContractError;
 const MAX_LIMIT: u64 = 50;
 const DEFAULT_LIMIT: u64 = 10;
 
+#[cw_serde]
+pub enum AssetInfo {
+    Native { denom: String },
+    Cw20 { contract_addr: String },
+}
+
+impl AssetInfo {
+    /// Builds the message sending `amount` of this asset from the contract to `recipient`.
+    pub fn transfer_msg(&self, recipient: &str, amount: Uint128) -> StdResult<CosmosMsg> {
+        Ok(match self {
+            AssetInfo::Native { denom } => CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(amount.u128(), denom),
+            }),
+            AssetInfo::Cw20 { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+        })
+    }
+}
+
+impl AssetInfo {
+    /// Storage key telling native denoms and cw20 contracts apart.
+    pub fn key(&self) -> String {
+        match self {
+            AssetInfo::Native { denom } => format!("native:{}", denom),
+            AssetInfo::Cw20 { contract_addr } => format!("cw20:{}", contract_addr),
+        }
+    }
+}
+
+impl fmt::Display for AssetInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetInfo::Native { denom } => write!(f, "{}", denom),
+            AssetInfo::Cw20 { contract_addr } => write!(f, "{}", contract_addr),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+/// How much a new bid has to exceed the current highest bid by. When both
+/// parts are set, the larger one applies.
+#[cw_serde]
+#[derive(Default)]
+pub struct BidIncrement {
+    pub amount: Uint128,
+    /// Percentage of the current highest bid, in basis points.
+    pub bps: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TokenAuctionState {
     pub start_time: Expiration,
     pub end_time: Expiration,
     pub high_bidder_addr: Addr,
     pub high_bidder_amount: Uint128,
-    pub coin_denom: String,
+    pub asset: AssetInfo,
     pub auction_id: Uint128,
     pub min_bid: Option<Uint128>,
+    pub buy_now_price: Option<Uint128>,
+    /// Milliseconds before `end_time` in which a bid extends the auction.
+    pub extension_window: Option<u64>,
+    /// Milliseconds the auction is kept open after such a bid.
+    pub extension_duration: Option<u64>,
+    pub min_increment: Option<BidIncrement>,
     pub owner: String,
     pub token_id: String,
     pub token_address: String,
     pub is_cancelled: bool,
+    /// Set once the NFT and funds of the auction have been settled.
+    #[serde(default)]
+    pub is_claimed: bool,
+    /// Hidden maximum of the leading proxy bid, if the leader bid by proxy.
+    /// The `AuctionState` and `CommittedBid` queries never return it.
+    #[serde(default)]
+    pub proxy_max: Option<Uint128>,
+    /// Assets accepted besides `asset`. Bids in them are compared by their
+    /// value in `asset`.
+    #[serde(default)]
+    pub accepted_assets: Vec<AssetInfo>,
+    /// What the leader actually paid, when it was not `asset`. The
+    /// `high_bidder_amount` is then its value in `asset`.
+    #[serde(default)]
+    pub high_bid_asset: Option<Asset>,
+    #[serde(default)]
+    pub auction_type: AuctionType,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum AuctionType {
+    /// Ascending bids; the highest bidder wins when the auction ends.
+    #[default]
+    English,
+    /// The price falls from `start_price` to `end_price` between the start and
+    /// end time. The first payment at the current price wins.
+    Dutch {
+        start_price: Uint128,
+        end_price: Uint128,
+        decay: PriceDecay,
+    },
+    /// Bidders commit to a hidden amount until `reveal_start`, then reveal it
+    /// before `end_time`.
+    Sealed {
+        reveal_start: Expiration,
+        pricing: SealedPricing,
+        /// Whether deposits that are never revealed go to the seller instead
+        /// of back to the bidder.
+        forfeit_unrevealed: bool,
+    },
+}
+
+#[cw_serde]
+pub enum SealedPricing {
+    /// The winner pays their own bid.
+    FirstPrice,
+    /// The winner pays the second highest bid, or `min_bid` if higher (Vickrey).
+    SecondPrice,
+}
+
+#[cw_serde]
+pub struct SealedBid {
+    /// sha256 of `"<auction_id>:<bidder>:<amount>:<salt>"`.
+    pub commitment: Binary,
+    pub deposit: Uint128,
+    /// Set once revealed.
+    pub amount: Option<Uint128>,
+}
+
+#[cw_serde]
+pub enum PriceDecay {
+    /// The price falls continuously.
+    Linear,
+    /// The price drops every `interval` milliseconds.
+    Stepwise { interval: u64 },
 }
 
 #[cw_serde]
@@ -32,12 +167,151 @@ pub struct Bid {
     pub timestamp: Timestamp,
 }
 
+#[cw_serde]
+pub struct Config {
+    pub admin: Addr,
+    pub fee_collector: Addr,
+    /// Protocol fee taken from every sale, in basis points.
+    pub fee_bps: u64,
+    /// Assets sales may be priced in. An empty list allows any asset.
+    pub allowed_assets: Vec<AssetInfo>,
+    /// When set, only collections added as `Allowed` may be auctioned or listed.
+    /// `Denied` collections are rejected either way.
+    #[serde(default)]
+    pub restrict_collections: bool,
+    /// Used by auctions that do not set their own `min_increment`.
+    pub default_min_increment: Option<BidIncrement>,
+    /// Converts bids in an auction's `accepted_assets` into its main asset.
+    #[serde(default)]
+    pub price_source: PriceSource,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+#[derive(Default)]
+pub enum PriceSource {
+    /// Rates set by the admin with `SetRate`.
+    #[default]
+    RateTable,
+    /// A contract answering `OracleQueryMsg::Price`.
+    Oracle { address: Addr },
+}
+
+// Units of the reference asset one unit of an asset is worth, keyed by
+// (asset, reference) AssetInfo::key.
+pub const RATES: Map<(&str, &str), Decimal> = Map::new("rates");
+
+/// `Config` as stored by 0.2.0, with a plain collection allow list.
+/// Only read by the migration.
+#[cw_serde]
+pub struct LegacyConfig {
+    pub admin: Addr,
+    pub fee_collector: Addr,
+    pub fee_bps: u64,
+    pub allowed_assets: Vec<AssetInfo>,
+    #[serde(default)]
+    pub allowed_collections: Vec<Addr>,
+    pub default_min_increment: Option<BidIncrement>,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// `TokenAuctionState` as stored before 0.2.0, priced in a native denom.
+/// Only read by the migration.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyTokenAuctionState {
+    pub start_time: Expiration,
+    pub end_time: Expiration,
+    pub high_bidder_addr: Addr,
+    pub high_bidder_amount: Uint128,
+    pub coin_denom: String,
+    pub auction_id: Uint128,
+    pub min_bid: Option<Uint128>,
+    pub owner: String,
+    pub token_id: String,
+    pub token_address: String,
+    pub is_cancelled: bool,
+}
+
+pub const LEGACY_TOKEN_AUCTION_STATE: Map<u128, LegacyTokenAuctionState> = Map::new("auction_token_state");
+
+#[cw_serde]
+pub enum CollectionStatus {
+    Allowed,
+    Denied,
+}
+
+#[cw_serde]
+pub struct CollectionInfo {
+    pub address: Addr,
+    pub status: CollectionStatus,
+    /// Display name.
+    pub name: Option<String>,
+    /// Overrides the marketplace `fee_bps` for sales in this collection.
+    pub fee_bps: Option<u64>,
+    /// Overrides the marketplace `allowed_assets` for this collection.
+    pub allowed_assets: Option<Vec<AssetInfo>>,
+}
+
+// Admin-managed allow and deny lists, with per-collection overrides.
+pub const COLLECTIONS: Map<&Addr, CollectionInfo> = Map::new("collections");
+
 pub const NEXT_AUCTION_ID: Item<Uint128> = Item::new("next_auction_id");
 
 pub const BIDS: Map<u128, Vec<Bid>> = Map::new("bids"); // auction_id -> [bids]
 
 pub const TOKEN_AUCTION_STATE: Map<u128, TokenAuctionState> = Map::new("auction_token_state");
 
+// Funds each bidder has put into an auction, a proxy bid's whole maximum
+// included. Outbid bidders keep theirs committed to top up, and take them back
+// with RetractBid, during the auction or after it closes. Closing only settles
+// the highest bidder's entry.
+pub const COMMITTED_BIDS: Map<(u128, &Addr), Uint128> = Map::new("committed_bids");
+
+// Asset of the committed funds, for bidders who did not pay in the auction's asset.
+pub const COMMITTED_ASSETS: Map<(u128, &Addr), AssetInfo> = Map::new("committed_assets");
+
+// Commitment and deposit of each sealed bid, keyed by (auction_id, bidder).
+pub const SEALED_BIDS: Map<(u128, &Addr), SealedBid> = Map::new("sealed_bids");
+
+// Second highest revealed bid of sealed auctions.
+pub const SECOND_PRICES: Map<u128, Uint128> = Map::new("second_prices");
+
+/// A token held by the contract as part of a bundle.
+#[cw_serde]
+pub struct BundleItem {
+    pub token_address: String,
+    pub token_id: String,
+}
+
+// Tokens a seller has sent for a bundle that is not auctioned yet.
+pub const BUNDLE_DRAFTS: Map<&Addr, Vec<BundleItem>> = Map::new("bundle_drafts");
+
+// Every token of a bundle auction. The first one is the token recorded on its
+// TokenAuctionState.
+pub const BUNDLES: Map<u128, Vec<BundleItem>> = Map::new("bundles"); // auction_id -> [tokens]
+
+// Refunds the contract made on its own, such as an unsold highest bid or a
+// losing sealed bid, waiting to be withdrawn. Keyed by (owner, AssetInfo::key).
+pub const BALANCES: Map<(&Addr, &str), Asset> = Map::new("balances");
+
+// Addresses that have refunds sent to them instead of credited.
+pub const AUTO_SEND: Map<&Addr, bool> = Map::new("auto_send");
+
+/// Who may bid on a private auction.
+#[cw_serde]
+pub enum BidderAllowlist {
+    Addresses { addresses: Vec<Addr> },
+    /// Owners of at least one token of the cw721 collection.
+    Holders { collection: Addr },
+}
+
+pub const BIDDER_ALLOWLISTS: Map<u128, BidderAllowlist> = Map::new("bidder_allowlists"); // auction_id -> allowlist
+
+// Kept apart from TokenAuctionState so the AuctionState query does not reveal it.
+pub const RESERVE_PRICES: Map<u128, Uint128> = Map::new("reserve_prices"); // auction_id -> reserve price
+
 #[cw_serde]
 pub enum OrderBy {
     Asc,
@@ -69,11 +343,36 @@ impl<'a> IndexList<AuctionInfo> for AuctionIdIndices<'a> {
 }
 
 pub struct AuctionIdIndices<'a> {
-    pub token: MultiIndex<'a, String, AuctionInfo, String>,
+    pub token: MultiIndex<'a, String, AuctionInfo, (&'a Addr, &'a str)>,
 }
 
-pub fn auction_infos<'a>() -> IndexedMap<'a, &'a str, AuctionInfo, AuctionIdIndices<'a>> {
+// (token_address, token_id) -> auction info
+pub fn auction_infos<'a>() -> IndexedMap<'a, (&'a Addr, &'a str), AuctionInfo, AuctionIdIndices<'a>> {
     let indexes = AuctionIdIndices {
+        token: MultiIndex::new(
+            |_pk: &[u8], r| r.token_address.clone(),
+            "auction_infos",
+            "auction_infos__token",
+        ),
+    };
+    IndexedMap::new("auction_infos", indexes)
+}
+
+pub struct LegacyAuctionIdIndices<'a> {
+    pub token: MultiIndex<'a, String, AuctionInfo, String>,
+}
+
+impl<'a> IndexList<AuctionInfo> for LegacyAuctionIdIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<AuctionInfo>> + '_> {
+        let v: Vec<&dyn Index<AuctionInfo>> = vec![&self.token];
+        Box::new(v.into_iter())
+    }
+}
+
+/// `auction_infos` as it was stored before the tuple key, keyed by
+/// `token_id + token_address`. Only read by the migration.
+pub fn legacy_auction_infos<'a>() -> IndexedMap<'a, &'a str, AuctionInfo, LegacyAuctionIdIndices<'a>> {
+    let indexes = LegacyAuctionIdIndices {
         token: MultiIndex::new(
             |_pk: &[u8], r| r.token_address.clone(),
             "ownership",
@@ -83,6 +382,107 @@ pub fn auction_infos<'a>() -> IndexedMap<'a, &'a str, AuctionInfo, AuctionIdIndi
     IndexedMap::new("ownership", indexes)
 }
 
+#[cw_serde]
+pub struct Listing {
+    pub token_id: String,
+    pub token_address: String,
+    pub seller: String,
+    pub price: Uint128,
+    pub denom: String,
+    pub expires: Expiration,
+}
+
+pub struct ListingIndices<'a> {
+    pub seller: MultiIndex<'a, String, Listing, (&'a str, &'a str)>,
+}
+
+impl<'a> IndexList<Listing> for ListingIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.seller];
+        Box::new(v.into_iter())
+    }
+}
+
+// (token_address, token_id) -> listing
+pub fn listings<'a>() -> IndexedMap<'a, (&'a str, &'a str), Listing, ListingIndices<'a>> {
+    let indexes = ListingIndices {
+        seller: MultiIndex::new(
+            |_pk: &[u8], r| r.seller.clone(),
+            "listings",
+            "listings__seller",
+        ),
+    };
+    IndexedMap::new("listings", indexes)
+}
+
+#[cw_serde]
+pub struct Offer {
+    pub token_address: String,
+    pub token_id: String,
+    pub bidder: String,
+    /// Funds held in escrow until the offer is accepted or cancelled.
+    pub asset: Asset,
+    pub expires: Expiration,
+}
+
+pub struct OfferIndices<'a> {
+    pub bidder: MultiIndex<'a, String, Offer, (&'a str, &'a str, &'a str)>,
+}
+
+impl<'a> IndexList<Offer> for OfferIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Offer>> + '_> {
+        let v: Vec<&dyn Index<Offer>> = vec![&self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+// (token_address, token_id, bidder) -> offer
+pub fn offers<'a>() -> IndexedMap<'a, (&'a str, &'a str, &'a str), Offer, OfferIndices<'a>> {
+    let indexes = OfferIndices {
+        bidder: MultiIndex::new(
+            |_pk: &[u8], r| r.bidder.clone(),
+            "offers",
+            "offers__bidder",
+        ),
+    };
+    IndexedMap::new("offers", indexes)
+}
+
+/// An offer on any token of a collection.
+#[cw_serde]
+pub struct CollectionOffer {
+    pub token_address: String,
+    pub bidder: String,
+    pub asset: AssetInfo,
+    /// Paid for each token.
+    pub price: Uint128,
+    /// Tokens still wanted. `price * quantity` is held in escrow.
+    pub quantity: u32,
+    pub expires: Expiration,
+}
+
+pub struct CollectionOfferIndices<'a> {
+    pub bidder: MultiIndex<'a, String, CollectionOffer, (&'a str, &'a str)>,
+}
+
+impl<'a> IndexList<CollectionOffer> for CollectionOfferIndices<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<CollectionOffer>> + '_> {
+        let v: Vec<&dyn Index<CollectionOffer>> = vec![&self.bidder];
+        Box::new(v.into_iter())
+    }
+}
+
+// (token_address, bidder) -> collection offer
+pub fn collection_offers<'a>() -> IndexedMap<'a, (&'a str, &'a str), CollectionOffer, CollectionOfferIndices<'a>> {
+    let indexes = CollectionOfferIndices {
+        bidder: MultiIndex::new(
+            |_pk: &[u8], r| r.bidder.clone(),
+            "collection_offers",
+            "collection_offers__bidder",
+        ),
+    };
+    IndexedMap::new("collection_offers", indexes)
+}
 
 pub fn read_bids(
     storage: &dyn Storage,
@@ -126,34 +526,207 @@ pub fn read_bids(
 pub fn read_auction_infos(
     storage: &dyn Storage,
     token_address: Option<String>,
-    start_after: Option<String>,
+    start_after: Option<(String, String)>,
     limit: Option<u64>,
 ) -> Result<Vec<AuctionInfo>, ContractError> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
+    let start_addr = start_after.as_ref().map(|(address, _)| Addr::unchecked(address));
+    let start = match (&start_addr, &start_after) {
+        (Some(address), Some((_, id))) => Some(Bound::exclusive((address, id.as_str()))),
+        _ => None,
+    };
 
-    let keys: Vec<String> = match token_address{
+    let res = match token_address {
         Some(val) => auction_infos()
             .idx
             .token
             .prefix(val)
-            .keys(storage, start, None, Order::Ascending)
+            .range(storage, start, None, Order::Ascending)
             .take(limit)
-            .collect::<Result<Vec<String>, _>>()?,
+            .map(|item| item.map(|(_, auction_info)| auction_info))
+            .collect::<StdResult<Vec<AuctionInfo>>>()?,
         None => auction_infos()
-            .idx
-            .token
-            .keys(storage, None, None, Order::Ascending)
+            .range(storage, start, None, Order::Ascending)
             .take(limit)
-            .collect::<Result<Vec<String>, _>>()?,
+            .map(|item| item.map(|(_, auction_info)| auction_info))
+            .collect::<StdResult<Vec<AuctionInfo>>>()?,
     };
-    let mut res: Vec<AuctionInfo> = vec![];
-    for key in keys.iter() {
-        res.push(auction_infos().load(storage, key)?);
-    }
     Ok(res)
 }
 
+pub fn read_collections(
+    storage: &dyn Storage,
+    status: Option<CollectionStatus>,
+    start_after: Option<String>,
+    limit: Option<u64>,
+) -> Result<Vec<CollectionInfo>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = start_after.map(Addr::unchecked);
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let collections = COLLECTIONS
+        .range(storage, start, None, Order::Ascending)
+        .filter(|item| match (item, &status) {
+            (Ok((_, collection)), Some(status)) => &collection.status == status,
+            _ => true,
+        })
+        .take(limit)
+        .map(|item| item.map(|(_, collection)| collection))
+        .collect::<StdResult<Vec<CollectionInfo>>>()?;
+    Ok(collections)
+}
+
+pub fn read_listings(
+    storage: &dyn Storage,
+    token_address: Option<String>,
+    seller: Option<String>,
+    start_after: Option<(String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<Listing>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(address, id)| Bound::exclusive((address.as_str(), id.as_str())));
+
+    let listings = match (seller, token_address) {
+        (Some(seller), token_address) => listings()
+            .idx
+            .seller
+            .prefix(seller)
+            .range(storage, start, None, Order::Ascending)
+            .filter(|item| match (item, &token_address) {
+                (Ok((_, listing)), Some(token_address)) => &listing.token_address == token_address,
+                _ => true,
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, listing)| listing))
+            .collect::<StdResult<Vec<Listing>>>()?,
+        (None, Some(token_address)) => {
+            // The cursor's token_address is implied by the prefix.
+            let start = start_after.as_ref().map(|(_, id)| Bound::exclusive(id.as_str()));
+            listings()
+                .prefix(&token_address)
+                .range(storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, listing)| listing))
+                .collect::<StdResult<Vec<Listing>>>()?
+        }
+        (None, None) => listings()
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, listing)| listing))
+            .collect::<StdResult<Vec<Listing>>>()?,
+    };
+    Ok(listings)
+}
+
+/// Offers filtered by bidder, collection or token. `token_id` only applies
+/// together with `token_address`.
+pub fn read_offers(
+    storage: &dyn Storage,
+    token_address: Option<String>,
+    token_id: Option<String>,
+    bidder: Option<String>,
+    start_after: Option<(String, String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<Offer>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(address, id, bidder)| Bound::exclusive((address.as_str(), id.as_str(), bidder.as_str())));
+
+    let offers = match (bidder, token_address, token_id) {
+        (Some(bidder), token_address, token_id) => offers()
+            .idx
+            .bidder
+            .prefix(bidder)
+            .range(storage, start, None, Order::Ascending)
+            .filter(|item| match item {
+                Ok((_, offer)) => {
+                    token_address.as_ref().is_none_or(|address| &offer.token_address == address)
+                        && token_id.as_ref().is_none_or(|id| &offer.token_id == id)
+                }
+                Err(_) => true,
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, offer)| offer))
+            .collect::<StdResult<Vec<Offer>>>()?,
+        (None, Some(token_address), Some(token_id)) => {
+            // The cursor's token is implied by the prefix.
+            let start = start_after
+                .as_ref()
+                .map(|(_, _, bidder)| Bound::exclusive(bidder.as_str()));
+            offers()
+                .prefix((&token_address, &token_id))
+                .range(storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, offer)| offer))
+                .collect::<StdResult<Vec<Offer>>>()?
+        }
+        (None, Some(token_address), None) => {
+            let start = start_after
+                .as_ref()
+                .map(|(_, id, bidder)| Bound::exclusive((id.as_str(), bidder.as_str())));
+            offers()
+                .sub_prefix(&token_address)
+                .range(storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, offer)| offer))
+                .collect::<StdResult<Vec<Offer>>>()?
+        }
+        (None, None, _) => offers()
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, offer)| offer))
+            .collect::<StdResult<Vec<Offer>>>()?,
+    };
+    Ok(offers)
+}
+
+pub fn read_collection_offers(
+    storage: &dyn Storage,
+    token_address: Option<String>,
+    bidder: Option<String>,
+    start_after: Option<(String, String)>,
+    limit: Option<u64>,
+) -> Result<Vec<CollectionOffer>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(address, bidder)| Bound::exclusive((address.as_str(), bidder.as_str())));
+
+    let offers = match (bidder, token_address) {
+        (Some(bidder), token_address) => collection_offers()
+            .idx
+            .bidder
+            .prefix(bidder)
+            .range(storage, start, None, Order::Ascending)
+            .filter(|item| match (item, &token_address) {
+                (Ok((_, offer)), Some(token_address)) => &offer.token_address == token_address,
+                _ => true,
+            })
+            .take(limit)
+            .map(|item| item.map(|(_, offer)| offer))
+            .collect::<StdResult<Vec<CollectionOffer>>>()?,
+        (None, Some(token_address)) => {
+            // The cursor's token_address is implied by the prefix.
+            let start = start_after.as_ref().map(|(_, bidder)| Bound::exclusive(bidder.as_str()));
+            collection_offers()
+                .prefix(&token_address)
+                .range(storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|item| item.map(|(_, offer)| offer))
+                .collect::<StdResult<Vec<CollectionOffer>>>()?
+        }
+        (None, None) => collection_offers()
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, offer)| offer))
+            .collect::<StdResult<Vec<CollectionOffer>>>()?,
+    };
+    Ok(offers)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,6 +758,43 @@ mod tests {
         ]
     }
 
+    #[test]
+    fn auction_infos_keys_do_not_collide() {
+        let mut deps = mock_dependencies();
+        let first = AuctionInfo {
+            auction_ids: vec![Uint128::from(1u128)],
+            token_address: "2abc".to_string(),
+            token_id: "1".to_string(),
+        };
+        let second = AuctionInfo {
+            auction_ids: vec![Uint128::from(2u128)],
+            token_address: "abc".to_string(),
+            token_id: "12".to_string(),
+        };
+        for info in [&first, &second] {
+            let token_address = Addr::unchecked(&info.token_address);
+            auction_infos()
+                .save(deps.as_mut().storage, (&token_address, &info.token_id), info)
+                .unwrap();
+        }
+
+        let infos = read_auction_infos(deps.as_ref().storage, None, None, None).unwrap();
+        // Composite keys are length-prefixed, so the shorter address sorts first.
+        assert_eq!(vec![second.clone(), first.clone()], infos);
+
+        let infos = read_auction_infos(
+            deps.as_ref().storage,
+            None,
+            Some(("abc".to_string(), "12".to_string())),
+            None,
+        )
+        .unwrap();
+        assert_eq!(vec![first.clone()], infos);
+
+        let infos = read_auction_infos(deps.as_ref().storage, Some("2abc".to_string()), None, None).unwrap();
+        assert_eq!(vec![first], infos);
+    }
+
     #[test]
     fn read_bids_no_params() {
         let mut deps = mock_dependencies();
@@ -373,4 +983,4 @@ mod tests {
         let bids = func(OrderBy::Desc);
         assert!(bids.is_empty());
     }
-}
\ No newline at end of file
+}